@@ -0,0 +1,78 @@
+//! Timings for `apply_slice` and `apply_write` over divisor-only rule sets.
+//!
+//! Run once with and once without the batch path to compare:
+//!
+//!     cargo bench --bench batch
+//!     cargo bench --bench batch --features simd
+
+use std::hint::black_box;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use fizzbuzz::{fizz_buzz, Fizzy, Matcher};
+
+const VALUES: usize = 4_000_000;
+const ROUNDS: usize = 5;
+
+fn best_of<F: FnMut()>(mut run: F) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            run();
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let ns_per_value = elapsed.as_nanos() as f64 / VALUES as f64;
+    println!(
+        "| {name:<28} | {:>9.1} ms | {ns_per_value:>6.2} ns |",
+        elapsed.as_secs_f64() * 1e3
+    );
+}
+
+fn five_rules<T: Copy + std::ops::Rem<Output = T> + PartialEq + From<u8>>() -> Fizzy<T> {
+    [3, 5, 7, 11, 13]
+        .into_iter()
+        .zip(["fizz", "buzz", "bazz", "fuzz", "bizz"])
+        .fold(Fizzy::new(), |fizzy, (divisor, word)| {
+            fizzy.add_matcher(Matcher::divisible_by(T::from(divisor), word))
+        })
+}
+
+fn main() {
+    let batch = if cfg!(feature = "simd") { "on" } else { "off" };
+    println!("batch path: {batch}, {VALUES} values, best of {ROUNDS}\n");
+    println!("| {:<28} | {:>12} | {:>9} |", "case", "time", "per value");
+    println!("|{:-<30}|{:->14}|{:->11}|", "", "", "");
+
+    let u32s = (1..=VALUES as u32).collect::<Vec<_>>();
+    let u64s = (1..=VALUES as u64).collect::<Vec<_>>();
+    let fizz_buzz_u32 = fizz_buzz::<u32>();
+    let fizz_buzz_u64 = fizz_buzz::<u64>();
+    let five_u64 = five_rules::<u64>();
+
+    report(
+        "apply_slice u32 fizz_buzz",
+        best_of(|| drop(black_box(fizz_buzz_u32.apply_slice(&u32s)))),
+    );
+    report(
+        "apply_slice u64 fizz_buzz",
+        best_of(|| drop(black_box(fizz_buzz_u64.apply_slice(&u64s)))),
+    );
+    report(
+        "apply_slice u64 five rules",
+        best_of(|| drop(black_box(five_u64.apply_slice(&u64s)))),
+    );
+    report(
+        "apply_write u64 fizz_buzz",
+        best_of(|| {
+            fizz_buzz_u64
+                .apply_write(1..=VALUES as u64, io::sink())
+                .unwrap()
+        }),
+    );
+    io::stdout().flush().unwrap();
+}
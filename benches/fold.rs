@@ -0,0 +1,62 @@
+//! Sum of output lengths through `for_each`, with and without the `fold`
+//! override on `Apply`.
+//!
+//!     cargo bench --bench fold
+//!
+//! The "next" case hides `Apply` behind `iter::from_fn`, so `for_each` falls
+//! back to a loop over `next`, as it did before the override.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use fizzbuzz::fizz_buzz;
+
+const VALUES: u64 = 4_000_000;
+const ROUNDS: usize = 5;
+
+fn best_of<F: FnMut() -> usize>(mut run: F) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(run());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let ns_per_value = elapsed.as_nanos() as f64 / VALUES as f64;
+    println!(
+        "| {name:<24} | {:>9.1} ms | {ns_per_value:>6.2} ns |",
+        elapsed.as_secs_f64() * 1e3
+    );
+}
+
+fn main() {
+    let fizzer = fizz_buzz::<u64>();
+    println!("sum of output lengths, {VALUES} values, best of {ROUNDS}\n");
+    println!("| {:<24} | {:>12} | {:>9} |", "case", "time", "per value");
+    println!("|{:-<26}|{:->14}|{:->11}|", "", "", "");
+
+    report(
+        "for_each via next",
+        best_of(|| {
+            let mut run = fizzer.clone().apply(1..=VALUES);
+            let mut total = 0;
+            std::iter::from_fn(|| run.next()).for_each(|label| total += label.len());
+            total
+        }),
+    );
+    report(
+        "for_each via fold",
+        best_of(|| {
+            let mut total = 0;
+            fizzer
+                .clone()
+                .apply(1..=VALUES)
+                .for_each(|label| total += label.len());
+            total
+        }),
+    );
+}
@@ -0,0 +1,58 @@
+//! Timings for `apply_by_chunks_parallel` against a sequential `apply` over
+//! ten million values.
+//!
+//!     cargo bench --bench parallel
+//!
+//! The speedup depends on the cores available; on a single core the parallel
+//! run only adds the cost of chunking and joining.
+
+use std::hint::black_box;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use fizzbuzz::fizz_buzz_sync;
+
+const VALUES: u64 = 10_000_000;
+const CHUNK_SIZE: usize = 100_000;
+const ROUNDS: usize = 3;
+
+fn best_of<F: FnMut() -> Vec<String>>(mut run: F) -> Duration {
+    (0..ROUNDS)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(run());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn report(name: &str, elapsed: Duration) {
+    let ns_per_value = elapsed.as_nanos() as f64 / VALUES as f64;
+    println!(
+        "| {name:<24} | {:>9.1} ms | {ns_per_value:>6.2} ns |",
+        elapsed.as_secs_f64() * 1e3
+    );
+}
+
+fn main() {
+    let fizzer = fizz_buzz_sync::<u64>();
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    println!("{VALUES} values, chunks of {CHUNK_SIZE}, {threads} threads, best of {ROUNDS}\n");
+    println!("| {:<24} | {:>12} | {:>9} |", "case", "time", "per value");
+    println!("|{:-<26}|{:->14}|{:->11}|", "", "", "");
+
+    let sequential = best_of(|| fizzer.clone().apply(1..=VALUES).collect());
+    report("apply", sequential);
+    let parallel = best_of(|| {
+        fizzer
+            .clone()
+            .apply_by_chunks_parallel(1..=VALUES, CHUNK_SIZE, threads)
+    });
+    report("apply_by_chunks_parallel", parallel);
+
+    println!(
+        "\nspeedup: {:.2}x",
+        sequential.as_secs_f64() / parallel.as_secs_f64()
+    );
+}
@@ -0,0 +1,124 @@
+//! Proptest strategies for random but valid rule sets, for fuzzing code
+//! that consumes `Fizzy` output.
+//!
+//! Every generated rule passes the checks of [`parse_rule`](crate::parse_rule):
+//! divisors are never zero and words are never empty.
+
+use std::ops::RangeInclusive;
+
+use proptest::collection::vec;
+use proptest::option;
+use proptest::prelude::*;
+
+use crate::{DivisorRule, Fizzy, RuleConfig};
+
+pub fn any_word() -> impl Strategy<Value = String> {
+    "[a-z]{1,8}"
+}
+
+/// A divisor rule with a divisor from `divisor_range` other than zero, and a
+/// limit on about one rule in four.
+///
+/// Panics if `divisor_range` holds no value besides zero.
+pub fn any_divisor_rule(
+    divisor_range: RangeInclusive<i64>,
+) -> impl Strategy<Value = DivisorRule<i64>> {
+    assert!(
+        divisor_range.clone().any(|divisor| divisor != 0),
+        "divisor_range must contain a non-zero divisor"
+    );
+    let divisors = divisor_range.prop_filter("divisor must not be zero", |divisor| *divisor != 0);
+    (divisors, any_word(), option::weighted(0.25, 1..=5usize)).prop_map(|(divisor, word, limit)| {
+        DivisorRule {
+            divisor,
+            word,
+            limit,
+        }
+    })
+}
+
+/// Up to `max_rules` divisor rules, for [`Fizzy::from_config`].
+pub fn any_divisor_ruleset(
+    max_rules: usize,
+    divisor_range: RangeInclusive<i64>,
+) -> impl Strategy<Value = RuleConfig<i64>> {
+    vec(any_divisor_rule(divisor_range), 0..=max_rules).prop_map(|rules| RuleConfig { rules })
+}
+
+/// A whole rule set, built from [`any_divisor_ruleset`].
+pub fn any_fizzy(
+    max_rules: usize,
+    divisor_range: RangeInclusive<i64>,
+) -> impl Strategy<Value = Fizzy<i64>> {
+    any_divisor_ruleset(max_rules, divisor_range).prop_map(|config| Fizzy::from_config(&config))
+}
+
+/// A rule set together with up to `max_inputs` values to run it over.
+pub fn any_ruleset_with_inputs(
+    max_rules: usize,
+    divisor_range: RangeInclusive<i64>,
+    max_inputs: usize,
+) -> impl Strategy<Value = (RuleConfig<i64>, Vec<i64>)> {
+    (
+        any_divisor_ruleset(max_rules, divisor_range),
+        vec(any::<i64>(), 0..=max_inputs),
+    )
+}
+
+impl Arbitrary for DivisorRule<i64> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<DivisorRule<i64>>;
+
+    fn arbitrary_with((): ()) -> Self::Strategy {
+        any_divisor_rule(-100..=100).boxed()
+    }
+}
+
+impl Arbitrary for RuleConfig<i64> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<RuleConfig<i64>>;
+
+    fn arbitrary_with((): ()) -> Self::Strategy {
+        any_divisor_ruleset(8, -100..=100).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_rule;
+
+    proptest! {
+        #[test]
+        fn rules_pass_validation(rule in any::<DivisorRule<i64>>()) {
+            let text = format!("{}={}", rule.divisor, rule.word);
+            prop_assert!(parse_rule::<i64>(&text).is_ok());
+        }
+
+        #[test]
+        fn apply_matches_eval_itemwise(
+            (config, inputs) in any_ruleset_with_inputs(5, -20..=20, 200)
+        ) {
+            let applied = Fizzy::from_config(&config)
+                .apply(inputs.iter().copied())
+                .collect::<Vec<_>>();
+            let fresh = Fizzy::from_config(&config);
+            let evaluated = inputs.iter().map(|&n| fresh.eval(n)).collect::<Vec<_>>();
+            prop_assert_eq!(applied, evaluated);
+            prop_assert_eq!(fresh.to_config(), Ok(config));
+        }
+
+        #[test]
+        fn whole_rule_sets_export(fizzy in any_fizzy(5, i64::MIN..=i64::MAX)) {
+            let config = fizzy.to_config().unwrap();
+            prop_assert!(config.rules.iter().all(|rule| rule.divisor != 0));
+            fizzy.eval(i64::MIN);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero divisor")]
+    fn zero_only_range_is_rejected() {
+        let _ = any_divisor_rule(0..=0);
+    }
+}
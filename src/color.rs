@@ -0,0 +1,31 @@
+use crate::hash::fnv1a_64;
+
+pub const RESET: &str = "\x1b[0m";
+
+/// The eight standard ANSI foreground colors, black through white.
+const PALETTE: [&str; 8] = [
+    "\x1b[30m", "\x1b[31m", "\x1b[32m", "\x1b[33m", "\x1b[34m", "\x1b[35m", "\x1b[36m", "\x1b[37m",
+];
+
+pub const N_COLORS: usize = PALETTE.len();
+
+/// A foreground escape code chosen by hashing `label`, so a label keeps its
+/// color across runs.
+pub fn label_to_ansi(label: &str) -> &'static str {
+    let hash = fnv1a_64(label.as_bytes());
+    // FNV's low bits mix poorly on their own; xor-fold before reducing.
+    let folded = (hash ^ (hash >> 32)) as u32;
+    PALETTE[folded as usize % N_COLORS]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stable_and_distinct() {
+        assert_eq!(label_to_ansi("fizz"), label_to_ansi("fizz"));
+        assert_eq!(label_to_ansi("fizz"), "\x1b[34m");
+        assert_eq!(label_to_ansi("buzz"), "\x1b[37m");
+    }
+}
@@ -0,0 +1,19 @@
+pub mod lzw;
+
+/// Decompresses each zlib stream, as produced by
+/// [`Fizzy::apply_label_zlib_compress`](crate::Fizzy::apply_label_zlib_compress).
+/// Streams that are not valid zlib or do not hold UTF-8 yield an error.
+#[cfg(feature = "flate2")]
+pub fn zlib_decompress_labels<I>(iter: I) -> impl Iterator<Item = std::io::Result<String>>
+where
+    I: Iterator<Item = Vec<u8>>,
+{
+    use flate2::read::ZlibDecoder;
+    use std::io::Read;
+
+    iter.map(|compressed| {
+        let mut label = String::new();
+        ZlibDecoder::new(compressed.as_slice()).read_to_string(&mut label)?;
+        Ok(label)
+    })
+}
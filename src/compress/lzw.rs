@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+/// Encodes the bytes of `s` as LZW codes over a fresh dictionary, in which
+/// codes below 256 stand for single bytes. Once every
+/// `u16` code is taken the dictionary stops growing.
+pub fn compress(s: &str) -> Vec<u16> {
+    let mut dictionary: HashMap<Vec<u8>, u16> =
+        (0..=u8::MAX).map(|b| (vec![b], b.into())).collect();
+    let mut codes = Vec::new();
+    let mut current = Vec::new();
+    for &b in s.as_bytes() {
+        current.push(b);
+        if dictionary.contains_key(&current) {
+            continue;
+        }
+        let next = dictionary.len();
+        if next <= usize::from(u16::MAX) {
+            dictionary.insert(current.clone(), next as u16);
+        }
+        current.pop();
+        codes.push(dictionary[&current]);
+        current = vec![b];
+    }
+    if !current.is_empty() {
+        codes.push(dictionary[&current]);
+    }
+    codes
+}
+
+/// Reverses [`compress`]. `None` if a code could not have been emitted by
+/// it or the bytes are not UTF-8.
+pub fn decompress(codes: &[u16]) -> Option<String> {
+    let mut dictionary: Vec<Vec<u8>> = (0..=u8::MAX).map(|b| vec![b]).collect();
+    let mut bytes = Vec::new();
+    let mut previous: Option<Vec<u8>> = None;
+    for &code in codes {
+        let code = usize::from(code);
+        let entry = match (dictionary.get(code), &previous) {
+            (Some(entry), _) => entry.clone(),
+            // The code being defined by this very step: previous + its first byte.
+            (None, Some(previous)) if code == dictionary.len() => {
+                let mut entry = previous.clone();
+                entry.push(previous[0]);
+                entry
+            }
+            _ => return None,
+        };
+        if let Some(mut previous) = previous.take() {
+            if dictionary.len() <= usize::from(u16::MAX) {
+                previous.push(entry[0]);
+                dictionary.push(previous);
+            }
+        }
+        bytes.extend_from_slice(&entry);
+        previous = Some(entry);
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Decodes each code stream with [`decompress`], as produced by
+/// [`Fizzy::apply_label_lzw_compress`](crate::Fizzy::apply_label_lzw_compress).
+pub fn decompress_labels<I>(iter: I) -> impl Iterator<Item = Option<String>>
+where
+    I: Iterator<Item = Vec<u16>>,
+{
+    iter.map(|codes| decompress(&codes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        for s in [
+            "",
+            "fizz",
+            "fizzbuzzfizzbuzz",
+            "aaaaaaa",
+            "abababab",
+            "über 🐝",
+        ] {
+            assert_eq!(decompress(&compress(s)).as_deref(), Some(s));
+        }
+    }
+
+    #[test]
+    fn known_codes() {
+        assert_eq!(compress("fizz"), [102, 105, 122, 122]);
+        assert_eq!(compress("aaaa"), [97, 256, 97]);
+        assert_eq!(compress("fizzbuzzfizzbuzz").len(), 11);
+    }
+
+    #[test]
+    fn invalid_codes() {
+        assert_eq!(decompress(&[256]), None);
+        assert_eq!(decompress(&[97, 300]), None);
+    }
+
+    #[test]
+    fn labels() {
+        let codes = vec![compress("fizz"), vec![256], compress("buzz")];
+        assert_eq!(
+            decompress_labels(codes.into_iter()).collect::<Vec<_>>(),
+            [Some("fizz".to_string()), None, Some("buzz".to_string())]
+        );
+    }
+}
@@ -0,0 +1,46 @@
+/// Levenshtein distance between `a` and `b`, counted in `char`s.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    edit_distance(&a, &b, None)
+}
+
+pub(crate) fn edit_distance<E: PartialEq>(a: &[E], b: &[E], band: Option<usize>) -> usize {
+    let band = band.map_or(usize::MAX, |band| band.max(a.len().abs_diff(b.len())));
+    // Out-of-band cells are unreachable; half of MAX keeps `+ 1` from overflowing.
+    let far = usize::MAX / 2;
+    let mut prev: Vec<usize> = (0..=b.len())
+        .map(|j| if j <= band { j } else { far })
+        .collect();
+    let mut curr = vec![far; b.len() + 1];
+    for (i, x) in a.iter().enumerate() {
+        let row = i + 1;
+        curr.fill(far);
+        if row <= band {
+            curr[0] = row;
+        }
+        let lo = row.saturating_sub(band).max(1);
+        let hi = row.saturating_add(band).min(b.len());
+        for j in lo..=hi {
+            let substitution = prev[j - 1] + usize::from(*x != b[j - 1]);
+            curr[j] = substitution.min(prev[j] + 1).min(curr[j - 1] + 1);
+        }
+        std::mem::swap(&mut prev, &mut curr);
+    }
+    prev[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levenshtein_known() {
+        assert_eq!(levenshtein("fizz", "fizz"), 0);
+        assert_eq!(levenshtein("buzz", "fizz"), 2);
+        assert_eq!(levenshtein("fizzbuzz", "fizz"), 4);
+        assert_eq!(levenshtein("", "buzz"), 4);
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("café", "cafe"), 1);
+    }
+}
@@ -0,0 +1,323 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// Percent-encodes every byte of `s` outside the RFC 3986 unreserved set
+/// (ASCII alphanumerics and `-_.~`) as `%XX`.
+pub fn percent_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for b in s.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(b as char)
+            }
+            _ => encoded.push_str(&format!("%{b:02X}")),
+        }
+    }
+    encoded
+}
+
+/// Reverses [`percent_encode`]. `None` if an escape is truncated or not hex,
+/// or if the decoded bytes are not UTF-8.
+pub fn percent_decode(s: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            let hex = tail.get(..2)?;
+            let digit = |b: u8| char::from(b).to_digit(16);
+            bytes.push((digit(hex[0])? * 16 + digit(hex[1])?) as u8);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Escapes the characters that are special in HTML text and quoted
+/// attribute values.
+pub fn html_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const MORSE_LETTERS: [&str; 26] = [
+    "·-", "-···", "-·-·", "-··", "·", "··-·", "--·", "····", "··", "·---", "-·-", "·-··", "--",
+    "-·", "---", "·--·", "--·-", "·-·", "···", "-", "··-", "···-", "·--", "-··-", "-·--", "--··",
+];
+
+const MORSE_DIGITS: [&str; 10] = [
+    "-----",
+    "·----",
+    "··---",
+    "···--",
+    "····-",
+    "·····",
+    "-····",
+    "--···",
+    "---··",
+    "----·",
+];
+
+pub(crate) fn morse_code(c: char) -> Option<&'static str> {
+    match c.to_ascii_uppercase() {
+        c @ 'A'..='Z' => Some(MORSE_LETTERS[(c as u8 - b'A') as usize]),
+        c @ '0'..='9' => Some(MORSE_DIGITS[(c as u8 - b'0') as usize]),
+        _ => None,
+    }
+}
+
+/// Encodes ASCII letters and digits as Morse code with `·` and `-`,
+/// separating characters with a space and words with ` / `. Other
+/// characters are kept as their own symbol.
+pub fn to_morse(s: &str) -> String {
+    s.split_whitespace()
+        .map(|word| {
+            word.chars()
+                .map(|c| morse_code(c).map_or_else(|| c.to_string(), str::to_string))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+/// Reverses [`to_morse`], yielding uppercase letters. Symbols that are not
+/// valid codes pass through.
+pub fn from_morse(s: &str) -> String {
+    s.split(" / ")
+        .map(|word| {
+            word.split(' ')
+                .map(|symbol| {
+                    MORSE_LETTERS
+                        .iter()
+                        .position(|&code| code == symbol)
+                        .map(|i| char::from(b'A' + i as u8).to_string())
+                        .or_else(|| {
+                            MORSE_DIGITS
+                                .iter()
+                                .position(|&code| code == symbol)
+                                .map(|i| char::from(b'0' + i as u8).to_string())
+                        })
+                        .unwrap_or_else(|| symbol.to_string())
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Writes each UTF-8 byte of `s` as eight binary digits, separated by
+/// spaces; non-ASCII characters span several bytes.
+pub fn to_binary_str(s: &str) -> String {
+    s.bytes()
+        .map(|b| format!("{b:08b}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Reverses [`to_binary_str`]. `None` if a group is not eight binary digits
+/// or the bytes are not UTF-8.
+pub fn from_binary_str(s: &str) -> Option<String> {
+    let bytes = s
+        .split_whitespace()
+        .map(|group| {
+            if group.len() != 8 || !group.bytes().all(|b| b == b'0' || b == b'1') {
+                return None;
+            }
+            u8::from_str_radix(group, 2).ok()
+        })
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes).ok()
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard RFC 4648 Base64 with `=` padding.
+pub fn base64_encode(input: &[u8]) -> String {
+    let mut encoded = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let group = u32::from_be_bytes([0, bytes[0], bytes[1], bytes[2]]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let sextet = (group >> (18 - 6 * i)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input length is not a multiple of four.
+    InvalidLength(usize),
+    /// The byte at this offset is outside the alphabet or is misplaced
+    /// padding.
+    InvalidByte(usize),
+    /// The decoded bytes are not UTF-8.
+    InvalidUtf8,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::InvalidLength(len) => {
+                write!(f, "base64 length {len} is not a multiple of 4")
+            }
+            DecodeError::InvalidByte(offset) => write!(f, "invalid base64 byte at offset {offset}"),
+            DecodeError::InvalidUtf8 => write!(f, "decoded bytes are not UTF-8"),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+/// Reverses [`base64_encode`].
+pub fn base64_decode(input: &str) -> Result<Vec<u8>, DecodeError> {
+    let input = input.as_bytes();
+    if !input.len().is_multiple_of(4) {
+        return Err(DecodeError::InvalidLength(input.len()));
+    }
+    let padding = input
+        .iter()
+        .rev()
+        .take(2)
+        .take_while(|&&b| b == b'=')
+        .count();
+    let data = &input[..input.len() - padding];
+    let mut decoded = Vec::with_capacity(input.len() / 4 * 3);
+    for (n, chunk) in data.chunks(4).enumerate() {
+        let mut group = 0u32;
+        for (i, &b) in chunk.iter().enumerate() {
+            let sextet = BASE64_ALPHABET
+                .iter()
+                .position(|&a| a == b)
+                .ok_or(DecodeError::InvalidByte(n * 4 + i))?;
+            group |= (sextet as u32) << (18 - 6 * i);
+        }
+        decoded.extend_from_slice(&group.to_be_bytes()[1..chunk.len()]);
+    }
+    Ok(decoded)
+}
+
+/// Decodes each string with [`base64_decode`], as produced by
+/// [`Fizzy::apply_label_base64`](crate::Fizzy::apply_label_base64).
+pub fn base64_decode_labels<I>(iter: I) -> impl Iterator<Item = Result<String, DecodeError>>
+where
+    I: Iterator,
+    I::Item: AsRef<str>,
+{
+    iter.map(|encoded| {
+        let bytes = base64_decode(encoded.as_ref())?;
+        String::from_utf8(bytes).map_err(|_| DecodeError::InvalidUtf8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_round_trip() {
+        assert_eq!(percent_encode("fizz"), "fizz");
+        assert_eq!(percent_encode("fizz buzz"), "fizz%20buzz");
+        assert_eq!(percent_encode("a-b_c.d~e"), "a-b_c.d~e");
+        assert_eq!(percent_encode("50%&é"), "50%25%26%C3%A9");
+        for s in ["fizz", "fizz buzz", "50%&é", "🐝?", ""] {
+            assert_eq!(percent_decode(&percent_encode(s)).as_deref(), Some(s));
+        }
+    }
+
+    #[test]
+    fn percent_decode_malformed() {
+        assert_eq!(percent_decode("%2"), None);
+        assert_eq!(percent_decode("%zz"), None);
+        assert_eq!(percent_decode("%+1"), None);
+        assert_eq!(percent_decode("%FF"), None);
+    }
+
+    #[test]
+    fn html_entities() {
+        assert_eq!(html_escape("fizz"), "fizz");
+        assert_eq!(html_escape("<b>"), "&lt;b&gt;");
+        assert_eq!(html_escape("a & b"), "a &amp; b");
+        assert_eq!(html_escape("\"fizz\""), "&quot;fizz&quot;");
+        assert_eq!(html_escape("it's"), "it&#x27;s");
+        assert_eq!(html_escape("&lt;"), "&amp;lt;");
+    }
+
+    #[test]
+    fn morse() {
+        assert_eq!(to_morse("SOS"), "··· --- ···");
+        assert_eq!(to_morse("sos"), "··· --- ···");
+        assert_eq!(
+            to_morse("fizz buzz"),
+            "··-· ·· --·· --·· / -··· ··- --·· --··"
+        );
+        assert_eq!(to_morse("15"), "·---- ·····");
+        assert_eq!(to_morse("a!"), "·- !");
+        assert_eq!(to_morse(""), "");
+        assert_eq!(from_morse("··· --- ···"), "SOS");
+        for s in ["fizz", "fizz buzz", "15", "a!"] {
+            let encoded = to_morse(s);
+            assert_eq!(to_morse(&from_morse(&encoded)), encoded);
+        }
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        assert_eq!(to_binary_str("fizz"), "01100110 01101001 01111010 01111010");
+        assert_eq!(to_binary_str("buzz"), "01100010 01110101 01111010 01111010");
+        assert_eq!(to_binary_str("é"), "11000011 10101001");
+        for s in ["fizz", "buzz", "é🐝", ""] {
+            assert_eq!(from_binary_str(&to_binary_str(s)).as_deref(), Some(s));
+        }
+        assert_eq!(from_binary_str("0110011"), None);
+        assert_eq!(from_binary_str("0110011x"), None);
+        assert_eq!(from_binary_str("11000011"), None);
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        let cases = [
+            ("fizz", "Zml6eg=="),
+            ("buzz", "YnV6eg=="),
+            ("fizzbuzz", "Zml6emJ1eno="),
+            ("", ""),
+            ("f", "Zg=="),
+            ("fiz", "Zml6"),
+        ];
+        for (plain, encoded) in cases {
+            assert_eq!(base64_encode(plain.as_bytes()), encoded);
+            assert_eq!(base64_decode(encoded), Ok(plain.as_bytes().to_vec()));
+        }
+    }
+
+    #[test]
+    fn base64_decode_errors() {
+        assert_eq!(base64_decode("Zml"), Err(DecodeError::InvalidLength(3)));
+        assert_eq!(base64_decode("Zm!6"), Err(DecodeError::InvalidByte(2)));
+        assert_eq!(base64_decode("Z=6e"), Err(DecodeError::InvalidByte(1)));
+        assert_eq!(base64_decode("Z==="), Err(DecodeError::InvalidByte(1)));
+    }
+}
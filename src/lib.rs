@@ -1,194 +1,2839 @@
-use std::fmt::Display;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::{self, Write};
 use std::marker::PhantomData;
 use std::ops::Rem;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
 
-pub struct Matcher<T> {
-    condition: Box<dyn Fn(T) -> bool>,
+#[cfg(feature = "proptest")]
+pub mod arbitrary;
+pub mod color;
+pub mod compress;
+pub mod distance;
+pub mod encode;
+pub mod hash;
+pub mod locale;
+pub mod phonetic;
+#[cfg(feature = "simd")]
+pub mod simd;
+pub mod transform;
+pub mod trie;
+
+use distance::edit_distance;
+use locale::NumberLocale;
+use trie::Trie;
+
+/// Whether the closures in a rule set may be shared between threads.
+///
+/// Rule sets are [`Local`] unless built from [`Fizzy::new_sync`], and only
+/// [`Shared`] ones can be evaluated from several threads at once.
+pub trait Threading: 'static {
+    type Predicate<T>: ?Sized + Fn(T) -> bool;
+    type Derived<T>: ?Sized + Fn(T) -> Option<String>;
+}
+
+/// Closures may capture anything, such as an `Rc` or a `Cell`.
+#[derive(Clone, Copy, Debug)]
+pub enum Local {}
+
+/// Closures must be `Send + Sync`.
+#[derive(Clone, Copy, Debug)]
+pub enum Shared {}
+
+impl Threading for Local {
+    type Predicate<T> = dyn Fn(T) -> bool;
+    type Derived<T> = dyn Fn(T) -> Option<String>;
+}
+
+impl Threading for Shared {
+    type Predicate<T> = dyn Fn(T) -> bool + Send + Sync;
+    type Derived<T> = dyn Fn(T) -> Option<String> + Send + Sync;
+}
+
+enum Condition<T, M: Threading> {
+    Predicate(Arc<M::Predicate<T>>),
+    Derived(Arc<M::Derived<T>>),
+    Divisor {
+        divisor: T,
+        divides: fn(T, &T) -> bool,
+        #[cfg(feature = "simd")]
+        mask: simd::Mask<T>,
+    },
+}
+
+impl<T: Clone, M: Threading> Clone for Condition<T, M> {
+    fn clone(&self) -> Condition<T, M> {
+        match self {
+            Condition::Predicate(predicate) => Condition::Predicate(Arc::clone(predicate)),
+            Condition::Derived(derived) => Condition::Derived(Arc::clone(derived)),
+            Condition::Divisor {
+                divisor,
+                divides,
+                #[cfg(feature = "simd")]
+                mask,
+            } => Condition::Divisor {
+                divisor: divisor.clone(),
+                divides: *divides,
+                #[cfg(feature = "simd")]
+                mask: *mask,
+            },
+        }
+    }
+}
+
+fn divides<T: Copy + Rem<Output = T> + PartialEq>(n: T, divisor: &T) -> bool {
+    n % *divisor == *divisor % *divisor
+}
+
+pub struct Matcher<T, M: Threading = Local> {
+    condition: Condition<T, M>,
     substitution: String,
+    limit: Option<Limit>,
+    depth: usize,
     _phantom: PhantomData<T>,
 }
 
+pub const DEFAULT_NESTING_LIMIT: usize = 64;
+
+pub const DEFAULT_HISTOGRAM_WIDTH: usize = 40;
+
+const DEFAULT_EMOJI: [(&str, &str); 3] = [("fizz", "🎵"), ("buzz", "🐝"), ("fizzbuzz", "🎶")];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NestingError {
+    pub depth: usize,
+    pub limit: usize,
+}
+
+impl Display for NestingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "nested rule sets are {} levels deep, more than the limit of {}",
+            self.depth, self.limit
+        )
+    }
+}
+
+impl Error for NestingError {}
+
+struct Limit {
+    max: usize,
+    hits: AtomicUsize,
+}
+
+impl Clone for Limit {
+    fn clone(&self) -> Limit {
+        Limit {
+            max: self.max,
+            hits: AtomicUsize::new(self.hits.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Closure conditions print as `<closure>`.
+impl<T: fmt::Debug, M: Threading> fmt::Debug for Matcher<T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = f.debug_struct("Matcher");
+        match &self.condition {
+            Condition::Divisor { divisor, .. } => s.field("divisor", divisor),
+            Condition::Predicate(_) | Condition::Derived(_) => {
+                s.field("condition", &format_args!("<closure>"))
+            }
+        };
+        s.field("substitution", &self.substitution)
+            .field("limit", &self.limit.as_ref().map(|limit| limit.max))
+            .finish()
+    }
+}
+
+impl<T: Clone, M: Threading> Clone for Matcher<T, M> {
+    fn clone(&self) -> Matcher<T, M> {
+        Matcher {
+            condition: self.condition.clone(),
+            substitution: self.substitution.clone(),
+            limit: self.limit.clone(),
+            depth: self.depth,
+            _phantom: PhantomData,
+        }
+    }
+}
+
 impl<T> Matcher<T> {
     pub fn new<F, S>(matcher: F, subs: S) -> Matcher<T>
     where
         F: 'static + Fn(T) -> bool,
         S: Into<String>,
     {
-        Matcher {
-            condition: Box::new(matcher),
-            substitution: subs.into(),
-            _phantom: PhantomData,
+        let predicate: Arc<dyn Fn(T) -> bool> = Arc::new(matcher);
+        Matcher::from_condition(Condition::Predicate(predicate), subs)
+    }
+
+    pub fn divisible_by<S>(divisor: T, subs: S) -> Matcher<T>
+    where
+        T: Copy + Rem<Output = T> + PartialEq,
+        S: Into<String>,
+    {
+        Matcher::divisor_rule(divisor, subs)
+    }
+
+    /// A matcher whose substitution is `inner.eval(derive(&value))` for values
+    /// accepted by `cond`. Fails if nesting would exceed
+    /// [`DEFAULT_NESTING_LIMIT`] levels; see [`Matcher::nested_with_limit`].
+    pub fn nested<C, D>(cond: C, derive: D, inner: Fizzy<T>) -> Result<Matcher<T>, NestingError>
+    where
+        C: 'static + Fn(&T) -> bool,
+        D: 'static + Fn(&T) -> T,
+        T: 'static + Clone + Display,
+    {
+        Matcher::nested_with_limit(cond, derive, inner, DEFAULT_NESTING_LIMIT)
+    }
+
+    /// Limits in `inner` are not reset when a run of the outer rule set starts.
+    pub fn nested_with_limit<C, D>(
+        cond: C,
+        derive: D,
+        inner: Fizzy<T>,
+        limit: usize,
+    ) -> Result<Matcher<T>, NestingError>
+    where
+        C: 'static + Fn(&T) -> bool,
+        D: 'static + Fn(&T) -> T,
+        T: 'static + Clone + Display,
+    {
+        let depth = inner.depth() + 1;
+        if depth > limit {
+            return Err(NestingError { depth, limit });
         }
+        let derived: Arc<dyn Fn(T) -> Option<String>> =
+            Arc::new(move |value: T| cond(&value).then(|| inner.eval(derive(&value))));
+        Ok(Matcher {
+            depth,
+            ..Matcher::from_condition(Condition::Derived(derived), "")
+        })
     }
 
-    pub fn check(&self, value: T) -> Option<String> {
-        if (self.condition)(value) {
-            Some(self.substitution.clone())
-        } else {
-            None
+    pub fn compose<U, F>(self, mapper: F) -> Matcher<U>
+    where
+        F: 'static + Fn(U) -> T,
+        T: 'static,
+    {
+        let condition: Condition<U, Local> = match self.condition {
+            Condition::Predicate(predicate) => {
+                let predicate: Arc<dyn Fn(U) -> bool> =
+                    Arc::new(move |value| predicate(mapper(value)));
+                Condition::Predicate(predicate)
+            }
+            Condition::Derived(derived) => {
+                let derived: Arc<dyn Fn(U) -> Option<String>> =
+                    Arc::new(move |value| derived(mapper(value)));
+                Condition::Derived(derived)
+            }
+            Condition::Divisor {
+                divisor, divides, ..
+            } => {
+                let predicate: Arc<dyn Fn(U) -> bool> =
+                    Arc::new(move |value| divides(mapper(value), &divisor));
+                Condition::Predicate(predicate)
+            }
+        };
+        Matcher {
+            condition,
+            substitution: self.substitution,
+            limit: self.limit,
+            depth: self.depth,
+            _phantom: PhantomData,
         }
     }
 }
 
-pub struct Fizzy<T> {
-    matchers: Vec<Matcher<T>>,
-    _phantom: PhantomData<T>,
+impl<T> Matcher<T, Shared> {
+    /// Like [`Matcher::new`], for rule sets built with [`Fizzy::new_sync`].
+    pub fn new_sync<F, S>(matcher: F, subs: S) -> Matcher<T, Shared>
+    where
+        F: 'static + Fn(T) -> bool + Send + Sync,
+        S: Into<String>,
+    {
+        let predicate: Arc<dyn Fn(T) -> bool + Send + Sync> = Arc::new(matcher);
+        Matcher::from_condition(Condition::Predicate(predicate), subs)
+    }
+
+    /// Like [`Matcher::divisible_by`], for rule sets built with
+    /// [`Fizzy::new_sync`].
+    pub fn divisible_by_sync<S>(divisor: T, subs: S) -> Matcher<T, Shared>
+    where
+        T: Copy + Rem<Output = T> + PartialEq,
+        S: Into<String>,
+    {
+        Matcher::divisor_rule(divisor, subs)
+    }
 }
 
-impl<T> Fizzy<T> {
-    pub fn new() -> Self {
-        Fizzy {
-            matchers: Vec::new(),
+impl<T, M: Threading> Matcher<T, M> {
+    fn from_condition<S: Into<String>>(condition: Condition<T, M>, subs: S) -> Matcher<T, M> {
+        Matcher {
+            condition,
+            substitution: subs.into(),
+            limit: None,
+            depth: 0,
             _phantom: PhantomData,
         }
     }
 
-    pub fn add_matcher(mut self, matcher: Matcher<T>) -> Self {
-        self.matchers.push(matcher);
-        self
+    fn divisor_rule<S>(divisor: T, subs: S) -> Matcher<T, M>
+    where
+        T: Copy + Rem<Output = T> + PartialEq,
+        S: Into<String>,
+    {
+        let condition = Condition::Divisor {
+            divisor,
+            divides: divides::<T>,
+            #[cfg(feature = "simd")]
+            mask: simd::divisible_mask::<T>,
+        };
+        Matcher::from_condition(condition, subs)
     }
 
-    pub fn apply<I>(self, iter: I) -> impl Iterator<Item = String>
+    /// Like [`Matcher::divisor_rule`], except that a divisor of -1, which
+    /// every value is a multiple of, matches without computing `n % -1`:
+    /// that overflows when `n` is the minimum of a signed type.
+    fn checked_divisor_rule<S>(divisor: T, subs: S) -> Matcher<T, M>
     where
-        I: Iterator<Item = T>,
-        T: Clone + Display,
+        T: Copy + Rem<Output = T> + PartialEq + FromStr,
+        S: Into<String>,
     {
-        iter.map(move |val| {
-            let mut result = String::new();
-            for matcher in &self.matchers {
-                if let Some(substitution) = matcher.check(val.clone()) {
-                    result.push_str(&substitution);
-                }
+        if !"-1"
+            .parse::<T>()
+            .is_ok_and(|minus_one| minus_one == divisor)
+        {
+            return Matcher::divisor_rule(divisor, subs);
+        }
+        let condition = Condition::Divisor {
+            divisor,
+            divides: |_, _| true,
+            #[cfg(feature = "simd")]
+            mask: |_, _| u8::MAX,
+        };
+        Matcher::from_condition(condition, subs)
+    }
+
+    pub fn matches(&self, value: T) -> bool {
+        match &self.condition {
+            Condition::Predicate(predicate) => predicate(value),
+            Condition::Derived(derived) => derived(value).is_some(),
+            Condition::Divisor {
+                divisor, divides, ..
+            } => divides(value, divisor),
+        }
+    }
+
+    /// Emits the substitution for at most `n` matching values per run, after
+    /// which matching values are treated as unmatched. A run starts with each
+    /// `apply`-style call on the owning `Fizzy`; `eval` keeps counting.
+    ///
+    /// The counter is atomic: if a run is ever evaluated concurrently, exactly
+    /// `n` values still get the substitution, but which ones is unspecified.
+    pub fn limit(mut self, n: usize) -> Matcher<T, M> {
+        self.limit = Some(Limit {
+            max: n,
+            hits: AtomicUsize::new(0),
+        });
+        self
+    }
+
+    pub fn check(&self, value: T) -> Option<String> {
+        let substitution = match &self.condition {
+            Condition::Predicate(predicate) => {
+                predicate(value).then(|| self.substitution.clone())?
             }
-            if result.is_empty() {
-                val.to_string()
-            } else {
-                result
+            Condition::Derived(derived) => derived(value)?,
+            Condition::Divisor {
+                divisor, divides, ..
+            } => divides(value, divisor).then(|| self.substitution.clone())?,
+        };
+        if let Some(limit) = &self.limit {
+            let hit = limit
+                .hits
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |hits| {
+                    (hits < limit.max).then_some(hits + 1)
+                });
+            if hit.is_err() {
+                return None;
             }
-        })
+        }
+        Some(substitution)
+    }
+
+    fn divisor(&self) -> Option<&T> {
+        match &self.condition {
+            Condition::Divisor { divisor, .. } => Some(divisor),
+            _ => None,
+        }
+    }
+
+    fn reset(&self) {
+        if let Some(limit) = &self.limit {
+            limit.hits.store(0, Ordering::Relaxed);
+        }
+    }
+
+    pub fn sample<I>(&self, values: I) -> Vec<T>
+    where
+        I: IntoIterator<Item = T>,
+        T: Clone,
+    {
+        values
+            .into_iter()
+            .filter(|value| self.matches(value.clone()))
+            .collect()
+    }
+
+    pub fn sample_count<I>(&self, values: I) -> usize
+    where
+        I: IntoIterator<Item = T>,
+    {
+        values
+            .into_iter()
+            .map(|value| self.matches(value))
+            .filter(|hit| *hit)
+            .count()
     }
 }
 
-pub fn fizz_buzz<T>() -> Fizzy<T>
-where
-    T: Copy + Rem<Output = T> + From<u8> + PartialEq + Display,
-{
-    Fizzy::new()
-        .add_matcher(Matcher::new(|n: T| n % T::from(3) == T::from(0), "fizz"))
-        .add_matcher(Matcher::new(|n: T| n % T::from(5) == T::from(0), "buzz"))
+impl Matcher<usize> {
+    /// Fires at positions `offset`, `offset + period`, `offset + 2 * period`, ...
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn new_periodic<S>(period: usize, offset: usize, label: S) -> Matcher<usize>
+    where
+        S: Into<String>,
+    {
+        assert!(period > 0, "period must be positive");
+        Matcher::new(
+            move |pos: usize| pos >= offset && (pos - offset).is_multiple_of(period),
+            label,
+        )
+    }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRuleError {
+    MissingSeparator(String),
+    InvalidDivisor(String),
+    ZeroDivisor,
+    EmptyWord,
+}
 
-    #[test]
-    fn simple() {
-        let actual = fizz_buzz::<i32>().apply(1..=16).collect::<Vec<_>>();
-        let expected = [
-            "1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz", "buzz", "11", "fizz", "13",
-            "14", "fizzbuzz", "16",
-        ];
-        assert_eq!(actual, expected);
+impl Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseRuleError::MissingSeparator(rule) => {
+                write!(f, "rule {rule:?} is not of the form DIVISOR=WORD")
+            }
+            ParseRuleError::InvalidDivisor(divisor) => write!(f, "invalid divisor {divisor:?}"),
+            ParseRuleError::ZeroDivisor => write!(f, "divisor must not be zero"),
+            ParseRuleError::EmptyWord => write!(f, "substitution word must not be empty"),
+        }
     }
+}
 
-    #[test]
-    fn u8() {
-        let actual = fizz_buzz::<u8>().apply(1_u8..=16).collect::<Vec<_>>();
-        let expected = [
-            "1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz", "buzz", "11", "fizz", "13",
-            "14", "fizzbuzz", "16",
-        ];
-        assert_eq!(actual, expected);
+impl Error for ParseRuleError {}
+
+pub fn parse_rule<T>(rule: &str) -> Result<Matcher<T>, ParseRuleError>
+where
+    T: 'static + Copy + Rem<Output = T> + PartialEq + FromStr + TryFrom<u8>,
+{
+    let (divisor, word) = rule
+        .split_once('=')
+        .ok_or_else(|| ParseRuleError::MissingSeparator(rule.to_string()))?;
+    let divisor = divisor
+        .trim()
+        .parse::<T>()
+        .map_err(|_| ParseRuleError::InvalidDivisor(divisor.to_string()))?;
+    if T::try_from(0).is_ok_and(|zero| zero == divisor) {
+        return Err(ParseRuleError::ZeroDivisor);
+    }
+    if word.is_empty() {
+        return Err(ParseRuleError::EmptyWord);
     }
+    Ok(Matcher::checked_divisor_rule(divisor, word))
+}
 
-    #[test]
-    fn u64() {
-        let actual = fizz_buzz::<u64>().apply(1_u64..=16).collect::<Vec<_>>();
-        let expected = [
-            "1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz", "buzz", "11", "fizz", "13",
-            "14", "fizzbuzz", "16",
-        ];
-        assert_eq!(actual, expected);
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RuleConfig<T> {
+    pub rules: Vec<DivisorRule<T>>,
+}
+
+impl<T> RuleConfig<T> {
+    #[cfg(feature = "toml")]
+    pub fn to_toml_string(&self) -> Result<String, toml::ser::Error>
+    where
+        T: serde::Serialize,
+    {
+        toml::to_string(self)
     }
 
-    #[test]
-    fn nonsequential() {
-        let collatz_12 = &[12, 6, 3, 10, 5, 16, 8, 4, 2, 1];
-        let actual = fizz_buzz::<i32>()
-            .apply(collatz_12.iter().cloned())
-            .collect::<Vec<_>>();
-        let expected = vec![
-            "fizz", "fizz", "fizz", "buzz", "buzz", "16", "8", "4", "2", "1",
-        ];
-        assert_eq!(actual, expected);
+    #[cfg(feature = "toml")]
+    pub fn from_toml_str(s: &str) -> Result<RuleConfig<T>, toml::de::Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        toml::from_str(s)
     }
 
-    #[test]
-    fn custom() {
-        let expected = vec![
-            "1", "2", "Fizz", "4", "Buzz", "Fizz", "Bam", "8", "Fizz", "Buzz", "11", "Fizz", "13",
-            "Bam", "BuzzFizz", "16",
-        ];
-        let fizzer: Fizzy<i32> = Fizzy::new()
-            .add_matcher(Matcher::new(|n: i32| n % 5 == 0, "Buzz"))
-            .add_matcher(Matcher::new(|n: i32| n % 3 == 0, "Fizz"))
-            .add_matcher(Matcher::new(|n: i32| n % 7 == 0, "Bam"));
-        let actual = fizzer.apply(1..=16).collect::<Vec<_>>();
-        assert_eq!(actual, expected);
+    #[cfg(feature = "json")]
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error>
+    where
+        T: serde::Serialize,
+    {
+        serde_json::to_string(self)
     }
 
-    #[test]
-    fn f64() {
-        let actual = fizz_buzz::<f64>()
-            .apply(std::iter::successors(Some(1.0), |prev| Some(prev + 1.0)))
-            .take(16)
-            .collect::<Vec<_>>();
-        let expected = [
-            "1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz", "buzz", "11", "fizz", "13",
-            "14", "fizzbuzz", "16",
-        ];
-        assert_eq!(actual, expected);
+    #[cfg(feature = "json")]
+    pub fn from_json_str(s: &str) -> Result<RuleConfig<T>, serde_json::Error>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        serde_json::from_str(s)
     }
+}
 
-    #[test]
-    fn minimal_generic_bounds() {
-        use std::fmt;
-        use std::ops::{Add, Rem};
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DivisorRule<T> {
+    pub divisor: T,
+    pub word: String,
+    pub limit: Option<usize>,
+}
 
-        #[derive(Clone, Copy, Debug, Default, PartialEq)]
-        struct Fizzable(u8);
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportError {
+    pub opaque: Vec<usize>,
+}
 
-        impl From<u8> for Fizzable {
-            fn from(i: u8) -> Fizzable {
-                Fizzable(i)
-            }
-        }
+impl Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "matchers at indices {:?} are closures and cannot be exported",
+            self.opaque
+        )
+    }
+}
 
-        impl fmt::Display for Fizzable {
-            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-                let Fizzable(ref n) = self;
-                write!(f, "{n}")
-            }
-        }
+impl Error for ExportError {}
 
-        impl Add for Fizzable {
-            type Output = Fizzable;
-            fn add(self, rhs: Fizzable) -> Fizzable {
-                let Fizzable(n1) = self;
-                let Fizzable(n2) = rhs;
-                Fizzable(n1 + n2)
-            }
-        }
+/// `most_common_label` only considers substituted labels; ties go to the
+/// label seen first in the chunk.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkStats {
+    pub chunk_index: usize,
+    pub match_count: usize,
+    pub unique_labels: usize,
+    pub most_common_label: Option<String>,
+}
 
-        impl Rem for Fizzable {
-            type Output = Fizzable;
-            fn rem(self, rhs: Fizzable) -> Fizzable {
-                let Fizzable(n1) = self;
-                let Fizzable(n2) = rhs;
-                Fizzable(n1 % n2)
-            }
-        }
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListKind {
+    Ordered,
+    Unordered,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Align {
+    Left,
+    Right,
+    Center,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NormalizeReport {
+    pub merged: Vec<usize>,
+    pub removed: Vec<usize>,
+}
+
+pub struct Fizzy<T, M: Threading = Local> {
+    matchers: Vec<Matcher<T, M>>,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> Fizzy<T> {
+    pub fn new() -> Self {
+        Fizzy {
+            matchers: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn contramap<U, F>(self, mapper: F) -> Fizzy<U>
+    where
+        F: 'static + Fn(U) -> T,
+        T: 'static,
+    {
+        let mapper = Arc::new(mapper);
+        Fizzy {
+            matchers: self
+                .matchers
+                .into_iter()
+                .map(|matcher| {
+                    let mapper = Arc::clone(&mapper);
+                    matcher.compose(move |value| mapper(value))
+                })
+                .collect(),
+            _phantom: PhantomData,
+        }
+    }
+
+    pub fn from_config(config: &RuleConfig<T>) -> Fizzy<T>
+    where
+        T: 'static + Copy + Rem<Output = T> + PartialEq + FromStr,
+    {
+        config.rules.iter().fold(Fizzy::new(), |fizzy, rule| {
+            let matcher = Matcher::checked_divisor_rule(rule.divisor, rule.word.clone());
+            fizzy.add_matcher(match rule.limit {
+                Some(n) => matcher.limit(n),
+                None => matcher,
+            })
+        })
+    }
+}
+
+impl<T> Fizzy<T, Shared> {
+    /// An empty rule set whose matchers can be evaluated from several
+    /// threads, as [`Fizzy::apply_by_chunks_parallel`] does.
+    pub fn new_sync() -> Fizzy<T, Shared> {
+        Fizzy {
+            matchers: Vec::new(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<T, M: Threading> Fizzy<T, M> {
+    pub fn add_matcher(mut self, matcher: Matcher<T, M>) -> Self {
+        self.matchers.push(matcher);
+        self
+    }
+
+    /// Simplifies the divisor rules without changing the output of any value.
+    ///
+    /// Every matching rule appends its word, so duplicates are not redundant:
+    /// two "3 => fizz" rules yield "fizzfizz". What can be simplified is a run
+    /// of adjacent divisor rules sharing a divisor, which is merged into one
+    /// rule with the concatenated word, and a divisor rule with an empty word,
+    /// which is dropped. The report lists original indices of rules merged
+    /// into their predecessor and of rules removed. Opaque matchers are kept
+    /// as they are and break runs.
+    pub fn normalize(&mut self) -> NormalizeReport
+    where
+        T: Copy + Rem<Output = T> + PartialEq,
+    {
+        let mut report = NormalizeReport::default();
+        let mut kept: Vec<Matcher<T, M>> = Vec::with_capacity(self.matchers.len());
+        for (index, matcher) in self.matchers.drain(..).enumerate() {
+            let Some(&divisor) = matcher.divisor() else {
+                kept.push(matcher);
+                continue;
+            };
+            if matcher.limit.is_some() {
+                kept.push(matcher);
+                continue;
+            }
+            if matcher.substitution.is_empty() {
+                report.removed.push(index);
+                continue;
+            }
+            match kept.last_mut() {
+                Some(last) if last.limit.is_none() && last.divisor() == Some(&divisor) => {
+                    // Keep the condition as it is: parse_rule builds -1 rules with an
+                    // overflow-safe check that a fresh divisor rule would lose.
+                    last.substitution.push_str(&matcher.substitution);
+                    report.merged.push(index);
+                }
+                _ => kept.push(matcher),
+            }
+        }
+        self.matchers = kept;
+        report
+    }
+
+    pub fn to_config(&self) -> Result<RuleConfig<T>, ExportError>
+    where
+        T: Copy,
+    {
+        let mut rules = Vec::with_capacity(self.matchers.len());
+        let mut opaque = Vec::new();
+        for (index, matcher) in self.matchers.iter().enumerate() {
+            match matcher.divisor() {
+                Some(&divisor) => rules.push(DivisorRule {
+                    divisor,
+                    word: matcher.substitution.clone(),
+                    limit: matcher.limit.as_ref().map(|limit| limit.max),
+                }),
+                None => opaque.push(index),
+            }
+        }
+        if opaque.is_empty() {
+            Ok(RuleConfig { rules })
+        } else {
+            Err(ExportError { opaque })
+        }
+    }
+
+    fn depth(&self) -> usize {
+        self.matchers
+            .iter()
+            .map(|matcher| matcher.depth)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn start_run(&self) {
+        for matcher in &self.matchers {
+            matcher.reset();
+        }
+    }
+
+    fn substitute(&self, val: T) -> Option<String>
+    where
+        T: Clone,
+    {
+        let mut result = String::new();
+        for matcher in &self.matchers {
+            if let Some(substitution) = matcher.check(val.clone()) {
+                result.push_str(&substitution);
+            }
+        }
+        if result.is_empty() {
+            None
+        } else {
+            Some(result)
+        }
+    }
+
+    fn collect_labels<I>(&self, iter: I) -> Vec<String>
+    where
+        I: IntoIterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.start_run();
+        iter.into_iter().map(|val| self.eval(val)).collect()
+    }
+
+    pub fn eval(&self, val: T) -> String
+    where
+        T: Clone + Display,
+    {
+        match self.substitute(val.clone()) {
+            Some(result) => result,
+            None => val.to_string(),
+        }
+    }
+
+    pub fn apply<I>(self, iter: I) -> Apply<T, I, M>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.start_run();
+        Apply { fizzy: self, iter }
+    }
+
+    pub fn over<I>(self, src: I) -> BoundFizzy<T, I::IntoIter, M>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        BoundFizzy {
+            fizzy: self,
+            source: src.into_iter(),
+        }
+    }
+
+    pub fn apply_raw<I>(self, iter: I) -> impl Iterator<Item = (T, Option<String>)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone,
+    {
+        self.start_run();
+        iter.map(move |val| {
+            let substitution = self.substitute(val.clone());
+            (val, substitution)
+        })
+    }
+
+    pub fn apply_sparse<I>(self, iter: I) -> impl Iterator<Item = (T, String)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone,
+    {
+        self.apply_raw(iter)
+            .filter_map(|(val, substitution)| substitution.map(|label| (val, label)))
+    }
+
+    /// Like `apply(iter1.chain(iter2))`, except the second source is only
+    /// built by calling `iter2` once `iter1` is exhausted.
+    pub fn apply_chain_lazy<I1, I2, F>(self, iter1: I1, iter2: F) -> impl Iterator<Item = String>
+    where
+        I1: Iterator<Item = T>,
+        I2: IntoIterator<Item = T>,
+        F: FnOnce() -> I2,
+        T: Clone + Display,
+    {
+        self.apply(iter1.chain(std::iter::once_with(iter2).flatten()))
+    }
+
+    pub fn apply_write<I, W>(&self, iter: I, mut writer: W) -> io::Result<()>
+    where
+        I: Iterator<Item = T>,
+        W: Write,
+        T: Clone + Display,
+    {
+        self.start_run();
+        #[cfg(feature = "simd")]
+        let iter = self.batch_write(iter, &mut writer)?;
+        for val in iter {
+            writeln!(writer, "{}", self.eval(val))?;
+        }
+        Ok(())
+    }
+
+    pub fn apply_slice(&self, values: &[T]) -> Vec<String>
+    where
+        T: Clone + Display,
+    {
+        self.start_run();
+        #[cfg(feature = "simd")]
+        if let Some(labels) = self.batch_slice(values) {
+            return labels;
+        }
+        values.iter().map(|val| self.eval(val.clone())).collect()
+    }
+
+    /// Collects `iter`, splits it into chunks of `chunk_size` values and
+    /// hands them round-robin to `num_threads` scoped threads. The output is
+    /// in source order. Only rule sets built with [`Fizzy::new_sync`] can be
+    /// shared between the threads.
+    pub fn apply_by_chunks_parallel<I>(
+        self,
+        iter: I,
+        chunk_size: usize,
+        num_threads: usize,
+    ) -> Vec<String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display + Sync,
+        Fizzy<T, M>: Sync,
+    {
+        assert!(chunk_size > 0, "chunk_size must be positive");
+        assert!(num_threads > 0, "num_threads must be positive");
+        self.start_run();
+        let values = iter.collect::<Vec<_>>();
+        let chunks = values.chunks(chunk_size).collect::<Vec<_>>();
+        let mut labelled = vec![Vec::new(); chunks.len()];
+        thread::scope(|scope| {
+            let workers = (0..num_threads.min(chunks.len()))
+                .map(|worker| {
+                    let fizzy = &self;
+                    let chunks = &chunks;
+                    scope.spawn(move || {
+                        chunks
+                            .iter()
+                            .enumerate()
+                            .skip(worker)
+                            .step_by(num_threads)
+                            .map(|(index, chunk)| {
+                                let labels = chunk
+                                    .iter()
+                                    .map(|val| fizzy.eval(val.clone()))
+                                    .collect::<Vec<_>>();
+                                (index, labels)
+                            })
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect::<Vec<_>>();
+            for worker in workers {
+                let results = worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+                for (index, labels) in results {
+                    labelled[index] = labels;
+                }
+            }
+        });
+        labelled.concat()
+    }
+
+    pub fn apply_pad<I>(self, iter: I, width: usize, align: Align) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply_pad_with(iter, width, align, ' ')
+    }
+
+    pub fn apply_pad_with<I>(
+        self,
+        iter: I,
+        width: usize,
+        align: Align,
+        fill: char,
+    ) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter)
+            .map(move |label| pad(label, width, align, fill))
+    }
+
+    /// Wraps each matcher's substitution found in `color_map` between its
+    /// escape code and a reset, so "fizzbuzz" can carry two colors. Values
+    /// that fall through to the fallback are left uncolored.
+    pub fn apply_colorize<I>(
+        self,
+        iter: I,
+        color_map: HashMap<String, &'static str>,
+    ) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.start_run();
+        iter.map(move |val| {
+            let mut result = String::new();
+            for matcher in &self.matchers {
+                if let Some(substitution) = matcher.check(val.clone()) {
+                    match color_map.get(&substitution) {
+                        Some(code) if !substitution.is_empty() => {
+                            result.push_str(code);
+                            result.push_str(&substitution);
+                            result.push_str(color::RESET);
+                        }
+                        _ => result.push_str(&substitution),
+                    }
+                }
+            }
+            if result.is_empty() {
+                val.to_string()
+            } else {
+                result
+            }
+        })
+    }
+
+    pub fn apply_markdown_table<I>(self, iter: I) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.start_run();
+        let rows = iter
+            .map(|val| {
+                let label = self.eval(val.clone());
+                (
+                    escape_markdown_cell(&val.to_string()),
+                    escape_markdown_cell(&label),
+                )
+            })
+            .collect::<Vec<_>>();
+        let value_width = rows
+            .iter()
+            .map(|(value, _)| value.chars().count())
+            .fold("Value".len(), usize::max);
+        let label_width = rows
+            .iter()
+            .map(|(_, label)| label.chars().count())
+            .fold("Label".len(), usize::max);
+
+        let mut table = String::new();
+        push_markdown_row(&mut table, "Value", value_width, "Label", label_width);
+        table.push_str(&format!(
+            "| {} | {} |\n",
+            "-".repeat(value_width),
+            "-".repeat(label_width)
+        ));
+        for (value, label) in &rows {
+            push_markdown_row(&mut table, value, value_width, label, label_width);
+        }
+        table
+    }
+
+    pub fn apply_to_ron<I>(self, iter: I) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let mut ron = String::from("[");
+        for (i, label) in self.apply(iter).enumerate() {
+            if i > 0 {
+                ron.push_str(", ");
+            }
+            // RON strings take Rust escapes, such as `\u{7}` rather than `\u0007`.
+            ron.push_str(&format!("{label:?}"));
+        }
+        ron.push(']');
+        ron
+    }
+
+    /// Labels as a `labels` array under a `[fizzbuzz]` table. With the `toml`
+    /// feature the document is written by the `toml` crate.
+    pub fn apply_to_toml<I>(self, iter: I) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        #[cfg(feature = "toml")]
+        {
+            #[derive(serde::Serialize)]
+            struct Table {
+                labels: Vec<String>,
+            }
+
+            #[derive(serde::Serialize)]
+            struct Document {
+                fizzbuzz: Table,
+            }
+
+            let document = Document {
+                fizzbuzz: Table {
+                    labels: self.apply(iter).collect(),
+                },
+            };
+            toml::to_string(&document).expect("a table of strings always serializes")
+        }
+        #[cfg(not(feature = "toml"))]
+        {
+            let mut toml = String::from("[fizzbuzz]\nlabels = [");
+            for (i, label) in self.apply(iter).enumerate() {
+                if i > 0 {
+                    toml.push_str(", ");
+                }
+                push_quoted(&mut toml, &label);
+            }
+            toml.push_str("]\n");
+            toml
+        }
+    }
+
+    pub fn apply_distinct_count<I>(self, iter: I) -> (Vec<String>, usize)
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let labels = self.apply(iter).collect::<Vec<_>>();
+        let distinct = labels.iter().collect::<HashSet<_>>().len();
+        (labels, distinct)
+    }
+
+    pub fn apply_count_distinct_streaming<I>(self, iter: I) -> usize
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).collect::<HashSet<_>>().len()
+    }
+
+    pub fn apply_first_match<I>(self, mut iter: I) -> Option<(T, String)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone,
+    {
+        self.start_run();
+        iter.find_map(|val| self.substitute(val.clone()).map(|label| (val, label)))
+    }
+
+    pub fn apply_last_match<I>(self, iter: I) -> Option<(T, String)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone,
+    {
+        self.start_run();
+        iter.filter_map(|val| self.substitute(val.clone()).map(|label| (val, label)))
+            .last()
+    }
+
+    pub fn apply_nth_match<I>(self, iter: I, n: usize) -> Option<(T, String)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone,
+    {
+        self.start_run();
+        iter.filter_map(|val| self.substitute(val.clone()).map(|label| (val, label)))
+            .nth(n)
+    }
+
+    pub fn apply_count_matches_per_rule<I>(&self, iter: I) -> Vec<usize>
+    where
+        I: IntoIterator<Item = T>,
+        T: Clone,
+    {
+        let mut counts = vec![0; self.matchers.len()];
+        for val in iter {
+            for (count, matcher) in counts.iter_mut().zip(&self.matchers) {
+                if matcher.matches(val.clone()) {
+                    *count += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    pub fn display_over<I>(&self, src: I) -> DisplayOver<'_, T, I::IntoIter, M>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        DisplayOver {
+            fizzy: self,
+            source: Cell::new(Some(src.into_iter())),
+            terminator: "\n",
+        }
+    }
+
+    /// Borrows the rule set for a run over `src` that serializes as a
+    /// sequence of labels; see [`FizzyRun`].
+    #[cfg(feature = "serde")]
+    pub fn bind<I>(&self, src: I) -> FizzyRun<'_, T, I::IntoIter, M>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        FizzyRun {
+            fizzy: self,
+            source: Cell::new(Some(src.into_iter())),
+            pairs: false,
+        }
+    }
+
+    pub fn into_fn(self) -> impl Fn(T) -> String + 'static
+    where
+        T: 'static + Clone + Display,
+    {
+        move |val| self.eval(val)
+    }
+
+    pub fn into_fn_ref(self) -> Box<dyn Fn(&T) -> String>
+    where
+        T: 'static + Clone + Display,
+    {
+        Box::new(move |val| self.eval(val.clone()))
+    }
+
+    pub fn apply_json_lines<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| {
+            let mut line = String::with_capacity(label.len() + 3);
+            push_quoted(&mut line, &label);
+            line.push('\n');
+            line
+        })
+    }
+
+    pub fn apply_ssv<I>(self, iter: I) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.join_quoted(iter, ' ')
+    }
+
+    pub fn apply_tsv<I>(self, iter: I) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.join_quoted(iter, '\t')
+    }
+
+    fn join_quoted<I>(self, iter: I, separator: char) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let mut joined = String::new();
+        for (i, label) in self.apply(iter).enumerate() {
+            if i > 0 {
+                joined.push(separator);
+            }
+            if label.is_empty() || label.contains([separator, '"', '\n']) {
+                push_quoted(&mut joined, &label);
+            } else {
+                joined.push_str(&label);
+            }
+        }
+        joined
+    }
+
+    pub fn apply_html_list<I>(self, iter: I, kind: ListKind) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.html_list(iter, kind, None)
+    }
+
+    pub fn apply_html_list_with_class<I>(self, iter: I, kind: ListKind, class: &str) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.html_list(iter, kind, Some(class))
+    }
+
+    fn html_list<I>(self, iter: I, kind: ListKind, class: Option<&str>) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let tag = match kind {
+            ListKind::Ordered => "ol",
+            ListKind::Unordered => "ul",
+        };
+        let mut html = match class {
+            Some(class) => format!("<{tag} class=\"{}\">\n", encode::html_escape(class)),
+            None => format!("<{tag}>\n"),
+        };
+        for label in self.apply(iter) {
+            html.push_str("  <li>");
+            html.push_str(&encode::html_escape(&label));
+            html.push_str("</li>\n");
+        }
+        html.push_str(&format!("</{tag}>\n"));
+        html
+    }
+
+    pub fn apply_ascii_table<I>(self, iter: I) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        render_table(self.apply(iter), &ASCII_TABLE)
+    }
+
+    #[cfg(feature = "unicode-table")]
+    pub fn apply_unicode_table<I>(self, iter: I) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        render_table(self.apply(iter), &UNICODE_TABLE)
+    }
+
+    pub fn apply_frequencies<I>(self, iter: I) -> HashMap<String, usize>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let mut frequencies = HashMap::new();
+        for label in self.apply(iter) {
+            *frequencies.entry(label).or_insert(0) += 1;
+        }
+        frequencies
+    }
+
+    pub fn apply_histogram<I>(self, iter: I) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply_histogram_with_width(iter, DEFAULT_HISTOGRAM_WIDTH)
+    }
+
+    /// One row per label, most frequent first and ties in label order. The
+    /// most frequent label gets a bar of `width` characters.
+    pub fn apply_histogram_with_width<I>(self, iter: I, width: usize) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let mut frequencies = self.apply_frequencies(iter).into_iter().collect::<Vec<_>>();
+        frequencies.sort_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+        let max = frequencies.first().map_or(0, |(_, count)| *count);
+        let label_width = frequencies
+            .iter()
+            .map(|(label, _)| label.chars().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut histogram = String::new();
+        for (label, count) in frequencies {
+            let bar = "#".repeat((count * width + max / 2) / max);
+            histogram.push_str(&format!(
+                "{} | {} | {count}\n",
+                pad(label, label_width, Align::Left, ' '),
+                pad(bar, width, Align::Left, ' ')
+            ));
+        }
+        histogram
+    }
+
+    pub fn apply_enumerate_with_label_change<I>(
+        self,
+        iter: I,
+    ) -> impl Iterator<Item = (usize, String, bool)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter)
+            .enumerate()
+            .scan(None, |previous: &mut Option<String>, (index, label)| {
+                let changed = previous.as_ref().is_some_and(|previous| *previous != label);
+                *previous = Some(label.clone());
+                Some((index, label, changed))
+            })
+    }
+
+    pub fn apply_running_distinct<I>(self, iter: I) -> impl Iterator<Item = (String, bool)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter)
+            .scan(HashSet::new(), |seen: &mut HashSet<String>, label| {
+                let first = !seen.contains(&label);
+                if first {
+                    seen.insert(label.clone());
+                }
+                Some((label, first))
+            })
+    }
+
+    pub fn apply_label_hash<I>(self, iter: I) -> impl Iterator<Item = (String, u64)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| {
+            let hash = hash::fnv1a_64(label.as_bytes());
+            (label, hash)
+        })
+    }
+
+    pub fn apply_chunk_stats<I>(
+        self,
+        iter: I,
+        chunk_size: usize,
+    ) -> impl Iterator<Item = ChunkStats>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        assert!(chunk_size > 0, "chunk_size must be positive");
+        let mut raw = self.apply_raw(iter);
+        let mut chunk_index = 0;
+        std::iter::from_fn(move || {
+            let mut labels = HashSet::new();
+            let mut matched: Vec<(String, usize)> = Vec::new();
+            let mut match_count = 0;
+            for (val, substitution) in raw.by_ref().take(chunk_size) {
+                match substitution {
+                    Some(label) => {
+                        match_count += 1;
+                        match matched.iter_mut().find(|(seen, _)| *seen == label) {
+                            Some((_, count)) => *count += 1,
+                            None => matched.push((label.clone(), 1)),
+                        }
+                        labels.insert(label);
+                    }
+                    None => {
+                        labels.insert(val.to_string());
+                    }
+                }
+            }
+            if labels.is_empty() {
+                return None;
+            }
+            let most_common_label = matched
+                .iter()
+                .rev()
+                .max_by_key(|(_, count)| *count)
+                .map(|(label, _)| label.clone());
+            let stats = ChunkStats {
+                chunk_index,
+                match_count,
+                unique_labels: labels.len(),
+                most_common_label,
+            };
+            chunk_index += 1;
+            Some(stats)
+        })
+    }
+
+    pub fn apply_label_entropy<I>(self, iter: I) -> f64
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let frequencies = self.apply_frequencies(iter);
+        let total = frequencies.values().sum::<usize>() as f64;
+        frequencies
+            .values()
+            .map(|count| {
+                let p = *count as f64 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
+
+    pub fn apply_nth<I>(self, iter: I, n: usize) -> Option<String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).nth(n)
+    }
+
+    pub fn apply_any<I>(self, mut iter: I) -> bool
+    where
+        I: Iterator<Item = T>,
+        T: Clone,
+    {
+        self.start_run();
+        iter.any(|val| self.substitute(val).is_some())
+    }
+
+    pub fn apply_all<I>(self, mut iter: I) -> bool
+    where
+        I: Iterator<Item = T>,
+        T: Clone,
+    {
+        self.start_run();
+        iter.all(|val| self.substitute(val).is_some())
+    }
+
+    pub fn apply_none<I>(self, iter: I) -> bool
+    where
+        I: Iterator<Item = T>,
+        T: Clone,
+    {
+        !self.apply_any(iter)
+    }
+
+    pub fn apply_count_chars<I>(self, iter: I) -> usize
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| label.chars().count()).sum()
+    }
+
+    pub fn apply_count_bytes<I>(self, iter: I) -> usize
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| label.len()).sum()
+    }
+
+    pub fn apply_position_of<I>(self, iter: I, target_label: &str) -> Vec<usize>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter)
+            .enumerate()
+            .filter(|(_, label)| label == target_label)
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    pub fn apply_first_position_of<I>(self, iter: I, target: &str) -> Option<usize>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).position(|label| label == target)
+    }
+
+    pub fn apply_cumulative_match_rate<I>(self, iter: I) -> impl Iterator<Item = f64>
+    where
+        I: Iterator<Item = T>,
+        T: Clone,
+    {
+        self.apply_raw(iter)
+            .scan((0usize, 0usize), |(matched, total), (_, substitution)| {
+                *matched += usize::from(substitution.is_some());
+                *total += 1;
+                Some(*matched as f64 / *total as f64)
+            })
+    }
+
+    pub fn apply_with_source_index<I>(self, source: I) -> impl Iterator<Item = (usize, String)>
+    where
+        I: IntoIterator<Item = (usize, T)>,
+        T: Clone + Display,
+    {
+        self.start_run();
+        source
+            .into_iter()
+            .map(move |(index, val)| (index, self.eval(val)))
+    }
+
+    /// Lays labels out row by row in `cols` columns, each padded to the widest
+    /// label. Columns are separated by one space; rows carry no trailing
+    /// padding.
+    pub fn apply_multiline<I>(self, iter: I, cols: usize) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        assert!(cols > 0, "cols must be positive");
+        let labels: Vec<String> = self.apply(iter).collect();
+        let width = labels
+            .iter()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let rows = labels.len().div_ceil(cols);
+        let mut out = String::with_capacity(rows * cols * (width + 1));
+        for row in labels.chunks(cols) {
+            let (last, init) = row.split_last().expect("chunks are never empty");
+            for label in init {
+                out.push_str(&pad(label.clone(), width, Align::Left, ' '));
+                out.push(' ');
+            }
+            out.push_str(last);
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn apply_then_zip<I, J>(self, iter: I, other: J) -> impl Iterator<Item = (String, J::Item)>
+    where
+        I: Iterator<Item = T>,
+        J: IntoIterator,
+        T: Clone + Display,
+    {
+        self.apply(iter).zip(other)
+    }
+
+    /// Yields every window of exactly `n` consecutive labels; a trailing
+    /// partial window is dropped.
+    pub fn apply_label_ngrams<I>(self, iter: I, n: usize) -> impl Iterator<Item = Vec<String>>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        assert!(n > 0, "n must be positive");
+        self.apply(iter)
+            .scan(VecDeque::with_capacity(n), move |window, label| {
+                if window.len() == n {
+                    window.pop_front();
+                }
+                window.push_back(label);
+                Some((window.len() == n).then(|| window.iter().cloned().collect()))
+            })
+            .flatten()
+    }
+
+    pub fn apply_label_transitions<I>(self, iter: I) -> impl Iterator<Item = (String, String)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter)
+            .scan(None, |last: &mut Option<String>, label| {
+                let previous = last.replace(label.clone());
+                Some(previous.map(|previous| (previous, label)))
+            })
+            .flatten()
+    }
+
+    pub fn apply_label_runs<I>(self, iter: I) -> impl Iterator<Item = (String, usize)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let mut labels = self.apply(iter).peekable();
+        std::iter::from_fn(move || {
+            let label = labels.next()?;
+            let mut count = 1;
+            while labels.next_if_eq(&label).is_some() {
+                count += 1;
+            }
+            Some((label, count))
+        })
+    }
+
+    /// Ties go to the lexicographically smallest label.
+    pub fn apply_label_most_common<I>(self, iter: I) -> Option<(String, usize)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply_frequencies(iter)
+            .into_iter()
+            .min_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then_with(|| a.cmp(b)))
+    }
+
+    /// Ties go to the lexicographically smallest label.
+    pub fn apply_label_least_common<I>(self, iter: I) -> Option<(String, usize)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply_frequencies(iter)
+            .into_iter()
+            .min_by(|(a, a_count), (b, b_count)| a_count.cmp(b_count).then_with(|| a.cmp(b)))
+    }
+
+    /// Median of the labels' character counts, or NaN for an empty input.
+    pub fn apply_label_median_length<I>(self, iter: I) -> f64
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let mut lengths: Vec<usize> = self
+            .apply(iter)
+            .map(|label| label.chars().count())
+            .collect();
+        lengths.sort_unstable();
+        let mid = lengths.len() / 2;
+        match lengths.len() {
+            0 => f64::NAN,
+            len if len % 2 == 1 => lengths[mid] as f64,
+            _ => (lengths[mid - 1] + lengths[mid]) as f64 / 2.0,
+        }
+    }
+
+    /// Sample variance of the labels' byte lengths; 0.0 for fewer than two
+    /// labels.
+    pub fn apply_label_variance<I>(self, iter: I) -> f64
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let lengths: Vec<f64> = self.apply(iter).map(|label| label.len() as f64).collect();
+        if lengths.len() < 2 {
+            return 0.0;
+        }
+        let n = lengths.len() as f64;
+        let mean = lengths.iter().sum::<f64>() / n;
+        lengths.iter().map(|len| (len - mean).powi(2)).sum::<f64>() / (n - 1.0)
+    }
+
+    pub fn apply_label_stddev<I>(self, iter: I) -> f64
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply_label_variance(iter).sqrt()
+    }
+
+    /// Jaccard index of the distinct labels of two runs; two empty runs are
+    /// identical.
+    pub fn apply_label_jaccard<I1, I2>(self, iter1: I1, iter2: I2) -> f64
+    where
+        I1: Iterator<Item = T>,
+        I2: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let set1: HashSet<String> = self.collect_labels(iter1).into_iter().collect();
+        let set2: HashSet<String> = self.collect_labels(iter2).into_iter().collect();
+        let union = set1.union(&set2).count();
+        if union == 0 {
+            return 1.0;
+        }
+        set1.intersection(&set2).count() as f64 / union as f64
+    }
+
+    /// Cosine similarity of the label frequency vectors of two runs; 0.0 if
+    /// either run is empty.
+    pub fn apply_label_cosine_sim<I1, I2>(self, iter1: I1, iter2: I2) -> f64
+    where
+        I1: Iterator<Item = T>,
+        I2: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let count = |labels: Vec<String>| {
+            let mut frequencies = HashMap::new();
+            for label in labels {
+                *frequencies.entry(label).or_insert(0usize) += 1;
+            }
+            frequencies
+        };
+        let freq1 = count(self.collect_labels(iter1));
+        let freq2 = count(self.collect_labels(iter2));
+        let magnitude = |frequencies: &HashMap<String, usize>| {
+            frequencies
+                .values()
+                .map(|&n| (n * n) as f64)
+                .sum::<f64>()
+                .sqrt()
+        };
+        let norm = magnitude(&freq1) * magnitude(&freq2);
+        if norm == 0.0 {
+            return 0.0;
+        }
+        let dot: usize = freq1
+            .iter()
+            .filter_map(|(label, n)| freq2.get(label).map(|m| n * m))
+            .sum();
+        dot as f64 / norm
+    }
+
+    /// Levenshtein distance between the label sequences of two runs, each
+    /// label counting as one symbol.
+    pub fn apply_label_edit_distance<I1, I2>(self, iter1: I1, iter2: I2) -> usize
+    where
+        I1: Iterator<Item = T>,
+        I2: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let a = self.collect_labels(iter1);
+        let b = self.collect_labels(iter2);
+        edit_distance(&a, &b, None)
+    }
+
+    /// Like [`Fizzy::apply_label_edit_distance`], but only fills DP cells
+    /// within `band` of the diagonal (widened to the length difference).
+    /// The result is exact when the true distance is at most `band` and an
+    /// upper bound otherwise.
+    pub fn apply_label_edit_distance_banded<I1, I2>(
+        self,
+        iter1: I1,
+        iter2: I2,
+        band: usize,
+    ) -> usize
+    where
+        I1: Iterator<Item = T>,
+        I2: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let a = self.collect_labels(iter1);
+        let b = self.collect_labels(iter2);
+        edit_distance(&a, &b, Some(band))
+    }
+
+    /// Fraction of positions at which `self` and `other` produce the same
+    /// label; 1.0 for an empty input.
+    pub fn apply_label_zip_match_rate<I>(self, other: Fizzy<T, M>, iter: I) -> f64
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let values: Vec<T> = iter.collect();
+        if values.is_empty() {
+            return 1.0;
+        }
+        let agree = self
+            .collect_labels(values.iter().cloned())
+            .into_iter()
+            .zip(other.collect_labels(values.iter().cloned()))
+            .filter(|(a, b)| a == b)
+            .count();
+        agree as f64 / values.len() as f64
+    }
+
+    /// Average character count of the last `window` labels, or of all labels
+    /// so far while fewer than `window` have been seen.
+    pub fn apply_moving_average_label_len<I>(
+        self,
+        iter: I,
+        window: usize,
+    ) -> impl Iterator<Item = f64>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        assert!(window > 0, "window must be positive");
+        self.apply(iter).scan(
+            (VecDeque::with_capacity(window), 0usize),
+            move |(lengths, sum), label| {
+                if lengths.len() == window {
+                    *sum -= lengths.pop_front().unwrap_or(0);
+                }
+                let len = label.chars().count();
+                lengths.push_back(len);
+                *sum += len;
+                Some(*sum as f64 / lengths.len() as f64)
+            },
+        )
+    }
+
+    /// The distinct consecutive label pairs, sorted.
+    pub fn apply_label_pairs_sorted<I>(self, iter: I) -> Vec<(String, String)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let mut pairs: Vec<(String, String)> = self.apply_label_transitions(iter).collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    pub fn apply_label_pair_frequencies<I>(self, iter: I) -> HashMap<(String, String), usize>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let mut frequencies = HashMap::new();
+        for pair in self.apply_label_transitions(iter) {
+            *frequencies.entry(pair).or_insert(0) += 1;
+        }
+        frequencies
+    }
+
+    pub fn apply_label_prefix_tree<I>(self, iter: I) -> Trie<String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let mut trie = Trie::new();
+        for label in self.apply(iter) {
+            trie.insert(label);
+        }
+        trie
+    }
+
+    /// Merges the labels of two runs whose label streams are each already
+    /// sorted. If either is not sorted the output order is unspecified, but
+    /// every label still appears exactly once.
+    pub fn apply_label_sorted_merge<I1, I2>(self, iter1: I1, iter2: I2) -> Vec<String>
+    where
+        I1: Iterator<Item = T>,
+        I2: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let a = self.collect_labels(iter1);
+        let b = self.collect_labels(iter2);
+        let mut merged = Vec::with_capacity(a.len() + b.len());
+        let mut a = a.into_iter().peekable();
+        let mut b = b.into_iter().peekable();
+        while let (Some(x), Some(y)) = (a.peek(), b.peek()) {
+            let next = if x <= y { a.next() } else { b.next() };
+            merged.extend(next);
+        }
+        merged.extend(a);
+        merged.extend(b);
+        merged
+    }
+
+    /// Pairs each label with its value when it parses as a `u64`, i.e. when no
+    /// matcher substituted it.
+    pub fn apply_label_to_digit_sum<I>(self, iter: I) -> impl Iterator<Item = (String, Option<u64>)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| {
+            let parsed = label.parse().ok();
+            (label, parsed)
+        })
+    }
+
+    /// Like [`Fizzy::apply_label_to_digit_sum`], but yields the sum of the
+    /// decimal digits of numeric labels.
+    pub fn apply_label_digit_sum<I>(self, iter: I) -> impl Iterator<Item = (String, Option<u64>)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply_label_to_digit_sum(iter).map(|(label, parsed)| {
+            let sum = parsed.map(|_| {
+                label
+                    .chars()
+                    .filter_map(|c| c.to_digit(10))
+                    .map(u64::from)
+                    .sum()
+            });
+            (label, sum)
+        })
+    }
+
+    /// Rewrites numeric labels in `base`, using lowercase letters past 9;
+    /// substituted labels pass through.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `base` is not in `2..=36`.
+    pub fn apply_label_base_convert<I>(self, iter: I, base: u32) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        assert!((2..=36).contains(&base), "base must be in 2..=36");
+        self.apply(iter)
+            .map(move |label| match u64::from_str(&label) {
+                Ok(n) => to_base(n, base),
+                Err(_) => label,
+            })
+    }
+
+    pub fn apply_label_rot13<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| transform::rot13(&label))
+    }
+
+    pub fn apply_label_caesar<I>(self, iter: I, shift: i32) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter)
+            .map(move |label| transform::caesar_shift(&label, shift))
+    }
+
+    pub fn apply_label_reverse_str<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| label.chars().rev().collect())
+    }
+
+    /// Reverses the order of space-separated words in each label.
+    pub fn apply_label_reverse_words<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter)
+            .map(|label| label.split(' ').rev().collect::<Vec<_>>().join(" "))
+    }
+
+    /// Pairs each label with itself wrapped in the color
+    /// [`color::label_to_ansi`] picks for it.
+    pub fn apply_label_hash_color<I>(self, iter: I) -> impl Iterator<Item = (String, String)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| {
+            let colored = format!("{}{label}{}", color::label_to_ansi(&label), color::RESET);
+            (label, colored)
+        })
+    }
+
+    /// Pairs each label with its [`phonetic::soundex`] code, which is empty
+    /// for numeric labels.
+    pub fn apply_label_soundex<I>(self, iter: I) -> impl Iterator<Item = (String, String)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| {
+            let code = phonetic::soundex(&label);
+            (label, code)
+        })
+    }
+
+    /// Pairs each label with its [`phonetic::metaphone`] code.
+    pub fn apply_label_metaphone<I>(self, iter: I) -> impl Iterator<Item = (String, String)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| {
+            let code = phonetic::metaphone(&label);
+            (label, code)
+        })
+    }
+
+    /// Pairs each label with its number of whitespace-separated words.
+    pub fn apply_label_word_count<I>(self, iter: I) -> impl Iterator<Item = (String, usize)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| {
+            let words = label.split_whitespace().count();
+            (label, words)
+        })
+    }
+
+    /// Pairs each label with how often each of its characters occurs.
+    pub fn apply_label_char_histogram<I>(
+        self,
+        iter: I,
+    ) -> impl Iterator<Item = (String, HashMap<char, usize>)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| {
+            let mut histogram = HashMap::new();
+            for c in label.chars() {
+                *histogram.entry(c).or_insert(0) += 1;
+            }
+            (label, histogram)
+        })
+    }
+
+    /// Pairs each label with whether its characters read the same reversed.
+    pub fn apply_label_is_palindrome<I>(self, iter: I) -> impl Iterator<Item = (String, bool)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| {
+            let palindrome = label.chars().eq(label.chars().rev());
+            (label, palindrome)
+        })
+    }
+
+    /// Pairs each label with whether it uses every ASCII letter, ignoring
+    /// case.
+    pub fn apply_label_is_pangram<I>(self, iter: I) -> impl Iterator<Item = (String, bool)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| {
+            let letters: u32 = label
+                .bytes()
+                .filter(u8::is_ascii_alphabetic)
+                .fold(0, |seen, b| seen | 1 << (b.to_ascii_lowercase() - b'a'));
+            (label, letters == (1 << 26) - 1)
+        })
+    }
+
+    /// Pairs each label with whether it is a rearrangement of the characters
+    /// of `target`.
+    pub fn apply_label_is_anagram_of<I>(
+        self,
+        iter: I,
+        target: &str,
+    ) -> impl Iterator<Item = (String, bool)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let mut target: Vec<char> = target.chars().collect();
+        target.sort_unstable();
+        self.apply(iter).map(move |label| {
+            let mut chars: Vec<char> = label.chars().collect();
+            chars.sort_unstable();
+            (label, chars == target)
+        })
+    }
+
+    /// Pairs each label with its [`distance::levenshtein`] distance to
+    /// `target`.
+    pub fn apply_label_levenshtein_to<I>(
+        self,
+        iter: I,
+        target: &str,
+    ) -> impl Iterator<Item = (String, usize)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let target = target.to_string();
+        self.apply(iter).map(move |label| {
+            let distance = distance::levenshtein(&label, &target);
+            (label, distance)
+        })
+    }
+
+    /// The longest string every label starts with; empty when there are no
+    /// labels.
+    pub fn apply_label_longest_common_prefix<I>(self, iter: I) -> String
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let mut labels = self.apply(iter);
+        let Some(mut prefix) = labels.next() else {
+            return String::new();
+        };
+        for label in labels {
+            let common: usize = prefix
+                .chars()
+                .zip(label.chars())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a.len_utf8())
+                .sum();
+            prefix.truncate(common);
+            if prefix.is_empty() {
+                break;
+            }
+        }
+        prefix
+    }
+
+    /// Regroups the digits of labels that parse as `i64` per `locale`;
+    /// substituted labels pass through.
+    pub fn apply_label_format_number<I>(
+        self,
+        iter: I,
+        locale: NumberLocale,
+    ) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter)
+            .map(move |label| match i64::from_str(&label) {
+                Ok(n) => locale::format_integer(n, locale),
+                Err(_) => label,
+            })
+    }
+
+    /// Replaces each label found in `map` with its emoji; other labels pass
+    /// through.
+    pub fn apply_label_emoji_encode<I>(
+        self,
+        iter: I,
+        map: &HashMap<&str, &str>,
+    ) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        let map: HashMap<String, String> = map
+            .iter()
+            .map(|(label, emoji)| (label.to_string(), emoji.to_string()))
+            .collect();
+        self.apply(iter)
+            .map(move |label| map.get(&label).cloned().unwrap_or(label))
+    }
+
+    /// [`Fizzy::apply_label_emoji_encode`] with emoji for the classic
+    /// fizzbuzz labels.
+    pub fn apply_label_emoji_default<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply_label_emoji_encode(iter, &HashMap::from(DEFAULT_EMOJI))
+    }
+
+    /// Makes each label safe for a URL with [`encode::percent_encode`].
+    pub fn apply_label_url_encode<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| encode::percent_encode(&label))
+    }
+
+    /// Makes each label safe to embed in HTML with [`encode::html_escape`].
+    pub fn apply_label_html_encode<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| encode::html_escape(&label))
+    }
+
+    /// Encodes each label with [`encode::to_morse`].
+    pub fn apply_label_morse<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| encode::to_morse(&label))
+    }
+
+    /// Pairs each label with the number of dots and dashes in its Morse
+    /// encoding.
+    pub fn apply_label_morse_audio_pulse_count<I>(
+        self,
+        iter: I,
+    ) -> impl Iterator<Item = (String, usize)>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| {
+            let pulses = label
+                .chars()
+                .filter_map(encode::morse_code)
+                .map(|code| code.chars().count())
+                .sum();
+            (label, pulses)
+        })
+    }
+
+    /// Encodes each label with [`encode::to_binary_str`].
+    pub fn apply_label_binary<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| encode::to_binary_str(&label))
+    }
+
+    /// Encodes each label's UTF-8 bytes with [`encode::base64_encode`].
+    pub fn apply_label_base64<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter)
+            .map(|label| encode::base64_encode(label.as_bytes()))
+    }
+
+    /// Decodes labels produced by [`Fizzy::apply_label_base64`]; see
+    /// [`encode::base64_decode_labels`].
+    pub fn apply_label_base64_decode<I>(
+        self,
+        iter: I,
+    ) -> impl Iterator<Item = Result<String, encode::DecodeError>>
+    where
+        I: Iterator,
+        I::Item: AsRef<str>,
+    {
+        encode::base64_decode_labels(iter)
+    }
+
+    /// Title-cases each label with [`transform::title_case`].
+    pub fn apply_label_capitalize<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| transform::title_case(&label))
+    }
+
+    pub fn apply_label_upper<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| label.to_uppercase())
+    }
+
+    pub fn apply_label_lower<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| label.to_lowercase())
+    }
+
+    /// Turns each label into a URL slug with [`transform::slugify`].
+    pub fn apply_label_slugify<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| transform::slugify(&label))
+    }
+
+    /// Keeps the first `max_words` whitespace-separated words of each
+    /// multi-word label, joined by single spaces. Labels with at most one
+    /// word, or no more than `max_words`, pass through unchanged. A
+    /// `max_words` of 0 keeps no words, so every label becomes empty.
+    pub fn apply_label_truncate_words<I>(
+        self,
+        iter: I,
+        max_words: usize,
+    ) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(move |label| {
+            if max_words == 0 {
+                return String::new();
+            }
+            let words: Vec<&str> = label.split_whitespace().collect();
+            if words.len() <= max_words {
+                return label;
+            }
+            words[..max_words].join(" ")
+        })
+    }
+
+    /// Replaces each label with its initials from [`transform::abbreviate`].
+    pub fn apply_label_abbreviate<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(|label| transform::abbreviate(&label))
+    }
+
+    /// Like [`Fizzy::apply_label_abbreviate`], but the initials are
+    /// lowercase.
+    pub fn apply_label_abbreviate_lower<I>(self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply_label_abbreviate(iter)
+            .map(|initials| initials.to_lowercase())
+    }
+
+    /// Repeats each label `times` times back to back; zero gives empty
+    /// labels.
+    pub fn apply_label_repeat<I>(self, iter: I, times: usize) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter).map(move |label| label.repeat(times))
+    }
+
+    /// Separates the characters of each label with `fill`, using
+    /// [`transform::interleave_chars`].
+    pub fn apply_label_interleave_chars<I>(
+        self,
+        iter: I,
+        fill: char,
+    ) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter)
+            .map(move |label| transform::interleave_chars(&label, fill))
+    }
+
+    /// Wraps each label at `width` characters with [`transform::word_wrap`].
+    pub fn apply_label_wrap_width<I>(self, iter: I, width: usize) -> impl Iterator<Item = String>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter)
+            .map(move |label| transform::word_wrap(&label, width))
+    }
+
+    /// Compresses each label's UTF-8 bytes into a zlib stream.
+    #[cfg(feature = "flate2")]
+    pub fn apply_label_zlib_compress<I>(self, iter: I) -> impl Iterator<Item = Vec<u8>>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        use flate2::write::ZlibEncoder;
+        use flate2::Compression;
+
+        self.apply(iter).map(|label| {
+            let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+            encoder
+                .write_all(label.as_bytes())
+                .and_then(|()| encoder.finish())
+                .expect("writing to a Vec cannot fail")
+        })
+    }
+
+    /// Reverses [`Fizzy::apply_label_zlib_compress`]; see
+    /// [`compress::zlib_decompress_labels`].
+    #[cfg(feature = "flate2")]
+    pub fn apply_label_zlib_decompress<I>(self, iter: I) -> impl Iterator<Item = io::Result<String>>
+    where
+        I: Iterator<Item = Vec<u8>>,
+    {
+        compress::zlib_decompress_labels(iter)
+    }
+
+    /// Encodes each label with [`compress::lzw::compress`], starting every
+    /// label from a fresh dictionary.
+    pub fn apply_label_lzw_compress<I>(self, iter: I) -> impl Iterator<Item = Vec<u16>>
+    where
+        I: Iterator<Item = T>,
+        T: Clone + Display,
+    {
+        self.apply(iter)
+            .map(|label| compress::lzw::compress(&label))
+    }
+
+    /// Reverses [`Fizzy::apply_label_lzw_compress`], yielding `None` for
+    /// code streams it could not have produced; see
+    /// [`compress::lzw::decompress_labels`].
+    pub fn apply_label_lzw_decompress<I>(self, iter: I) -> impl Iterator<Item = Option<String>>
+    where
+        I: Iterator<Item = Vec<u16>>,
+    {
+        compress::lzw::decompress_labels(iter)
+    }
+}
+
+struct TableStyle {
+    top: [char; 4],
+    header: [char; 4],
+    bottom: [char; 4],
+    vertical: char,
+}
+
+const ASCII_TABLE: TableStyle = TableStyle {
+    top: ['+', '-', '+', '+'],
+    header: ['+', '-', '+', '+'],
+    bottom: ['+', '-', '+', '+'],
+    vertical: '|',
+};
+
+#[cfg(feature = "unicode-table")]
+const UNICODE_TABLE: TableStyle = TableStyle {
+    top: ['╒', '═', '╤', '╕'],
+    header: ['╞', '═', '╪', '╡'],
+    bottom: ['╘', '═', '╧', '╛'],
+    vertical: '│',
+};
+
+fn render_table<L>(labels: L, style: &TableStyle) -> String
+where
+    L: Iterator<Item = String>,
+{
+    let labels = labels.collect::<Vec<_>>();
+    let index_width = labels.len().saturating_sub(1).to_string().len();
+    let label_width = labels
+        .iter()
+        .map(|label| label.chars().count())
+        .fold("label".len(), usize::max);
+
+    let border = |[left, fill, middle, right]: [char; 4]| {
+        let mut line = String::new();
+        line.push(left);
+        line.extend(std::iter::repeat_n(fill, index_width + 2));
+        line.push(middle);
+        line.extend(std::iter::repeat_n(fill, label_width + 2));
+        line.push(right);
+        line.push('\n');
+        line
+    };
+    let row = |index: &str, label: &str| {
+        format!(
+            "{v} {} {v} {} {v}\n",
+            pad(index.to_string(), index_width, Align::Right, ' '),
+            pad(label.to_string(), label_width, Align::Left, ' '),
+            v = style.vertical
+        )
+    };
+
+    let mut table = border(style.top);
+    table.push_str(&row("#", "label"));
+    table.push_str(&border(style.header));
+    for (index, label) in labels.iter().enumerate() {
+        table.push_str(&row(&index.to_string(), label));
+    }
+    table.push_str(&border(style.bottom));
+    table
+}
+
+fn to_base(mut n: u64, base: u32) -> String {
+    let mut digits = Vec::new();
+    loop {
+        let digit = (n % u64::from(base)) as u32;
+        digits.push(char::from_digit(digit, base).expect("digit is below base"));
+        n /= u64::from(base);
+        if n == 0 {
+            break;
+        }
+    }
+    digits.iter().rev().collect()
+}
+
+fn push_quoted(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04X}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn escape_markdown_cell(cell: &str) -> String {
+    cell.replace('|', "\\|")
+}
+
+fn push_markdown_row(
+    table: &mut String,
+    value: &str,
+    value_width: usize,
+    label: &str,
+    label_width: usize,
+) {
+    table.push_str(&format!(
+        "| {} | {} |\n",
+        pad(value.to_string(), value_width, Align::Left, ' '),
+        pad(label.to_string(), label_width, Align::Left, ' ')
+    ));
+}
+
+impl<T: fmt::Debug, M: Threading> fmt::Debug for Fizzy<T, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Fizzy")
+            .field("matchers", &self.matchers)
+            .finish()
+    }
+}
+
+impl<T: Clone, M: Threading> Clone for Fizzy<T, M> {
+    fn clone(&self) -> Fizzy<T, M> {
+        Fizzy {
+            matchers: self.matchers.clone(),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Apply<T, I, M: Threading = Local> {
+    fizzy: Fizzy<T, M>,
+    iter: I,
+}
+
+impl<T, I, M: Threading> Apply<T, I, M> {
+    pub fn into_parts(self) -> (Fizzy<T, M>, I) {
+        (self.fizzy, self.iter)
+    }
+}
+
+impl<T, I, M: Threading> Iterator for Apply<T, I, M>
+where
+    I: Iterator<Item = T>,
+    T: Clone + Display,
+{
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        self.iter.next().map(|val| self.fizzy.eval(val))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+
+    // `try_fold` cannot be overridden on stable Rust, so short-circuiting
+    // adapters keep the default `next`-driven loop.
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, String) -> B,
+    {
+        let fizzy = self.fizzy;
+        self.iter
+            .fold(init, move |acc, val| f(acc, fizzy.eval(val)))
+    }
+}
+
+/// A rule set bound to its source; nothing is evaluated until iteration.
+#[derive(Clone)]
+pub struct BoundFizzy<T, I, M: Threading = Local> {
+    fizzy: Fizzy<T, M>,
+    source: I,
+}
+
+impl<T, I, M: Threading> BoundFizzy<T, I, M> {
+    pub fn into_parts(self) -> (Fizzy<T, M>, I) {
+        (self.fizzy, self.source)
+    }
+}
+
+impl<T, I, M: Threading> IntoIterator for BoundFizzy<T, I, M>
+where
+    I: Iterator<Item = T>,
+    T: Clone + Display,
+{
+    type Item = String;
+    type IntoIter = Apply<T, I, M>;
+
+    fn into_iter(self) -> Apply<T, I, M> {
+        self.fizzy.apply(self.source)
+    }
+}
+
+/// Streams a whole run into a formatter, each label followed by the
+/// terminator ("\n" by default).
+///
+/// The adapter is single-use: the source is taken on the first format, and
+/// formatting it again writes nothing. Width, fill and alignment flags are
+/// ignored; use `apply_pad` for per-item padding.
+pub struct DisplayOver<'a, T, I, M: Threading = Local> {
+    fizzy: &'a Fizzy<T, M>,
+    source: Cell<Option<I>>,
+    terminator: &'a str,
+}
+
+impl<'a, T, I, M: Threading> DisplayOver<'a, T, I, M> {
+    pub fn terminator(mut self, terminator: &'a str) -> Self {
+        self.terminator = terminator;
+        self
+    }
+}
+
+impl<T, I, M: Threading> Display for DisplayOver<'_, T, I, M>
+where
+    I: Iterator<Item = T>,
+    T: Clone + Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let Some(source) = self.source.take() else {
+            return Ok(());
+        };
+        self.fizzy.start_run();
+        for val in source {
+            f.write_str(&self.fizzy.eval(val))?;
+            f.write_str(self.terminator)?;
+        }
+        Ok(())
+    }
+}
+
+/// A run that serializes as a sequence, evaluating the source lazily while
+/// the serializer writes it, so no labels are collected first.
+///
+/// Like [`DisplayOver`], the adapter is single-use: the source is taken on
+/// the first serialization, and serializing it again yields an empty
+/// sequence.
+#[cfg(feature = "serde")]
+pub struct FizzyRun<'a, T, I, M: Threading = Local> {
+    fizzy: &'a Fizzy<T, M>,
+    source: Cell<Option<I>>,
+    pairs: bool,
+}
+
+#[cfg(feature = "serde")]
+impl<T, I, M: Threading> FizzyRun<'_, T, I, M> {
+    /// Serializes `(value, label)` pairs instead of labels alone.
+    pub fn pairs(mut self) -> Self {
+        self.pairs = true;
+        self
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, I, M: Threading> serde::Serialize for FizzyRun<'_, T, I, M>
+where
+    I: Iterator<Item = T>,
+    T: Clone + Display + serde::Serialize,
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeSeq;
+
+        let Some(source) = self.source.take() else {
+            return serializer.serialize_seq(Some(0))?.end();
+        };
+        let len = match source.size_hint() {
+            (lower, Some(upper)) if lower == upper => Some(lower),
+            _ => None,
+        };
+        self.fizzy.start_run();
+        let mut seq = serializer.serialize_seq(len)?;
+        for val in source {
+            let label = self.fizzy.eval(val.clone());
+            if self.pairs {
+                seq.serialize_element(&(val, label))?;
+            } else {
+                seq.serialize_element(&label)?;
+            }
+        }
+        seq.end()
+    }
+}
+
+impl<T> Default for Fizzy<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn pad(label: String, width: usize, align: Align, fill: char) -> String {
+    let len = label.chars().count();
+    if len >= width {
+        return label;
+    }
+    let total = width - len;
+    let (left, right) = match align {
+        Align::Left => (0, total),
+        Align::Right => (total, 0),
+        Align::Center => (total / 2, total - total / 2),
+    };
+    let mut padded = String::with_capacity(label.len() + total * fill.len_utf8());
+    padded.extend(std::iter::repeat_n(fill, left));
+    padded.push_str(&label);
+    padded.extend(std::iter::repeat_n(fill, right));
+    padded
+}
+
+/// The classic rule set: "fizz" for multiples of 3 and "buzz" for multiples
+/// of 5.
+///
+/// # Panics
+///
+/// Panics if 3 or 5 cannot be converted into `T`; use [`try_fizz_buzz`] to
+/// handle that case.
+pub fn fizz_buzz<T>() -> Fizzy<T>
+where
+    T: Copy + Rem<Output = T> + TryFrom<u8> + PartialEq + Display,
+{
+    try_fizz_buzz().unwrap_or_else(|_| divisors_out_of_range::<T>())
+}
+
+pub fn try_fizz_buzz<T>() -> Result<Fizzy<T>, T::Error>
+where
+    T: Copy + Rem<Output = T> + TryFrom<u8> + PartialEq + Display,
+{
+    Ok(Fizzy::new()
+        .add_matcher(Matcher::divisible_by(T::try_from(3)?, "fizz"))
+        .add_matcher(Matcher::divisible_by(T::try_from(5)?, "buzz")))
+}
+
+/// [`fizz_buzz`] as a rule set that can be shared between threads.
+///
+/// # Panics
+///
+/// Panics if 3 or 5 cannot be converted into `T`; use
+/// [`try_fizz_buzz_sync`] to handle that case.
+pub fn fizz_buzz_sync<T>() -> Fizzy<T, Shared>
+where
+    T: Copy + Rem<Output = T> + TryFrom<u8> + PartialEq + Display,
+{
+    try_fizz_buzz_sync().unwrap_or_else(|_| divisors_out_of_range::<T>())
+}
+
+/// [`try_fizz_buzz`] as a rule set that can be shared between threads.
+pub fn try_fizz_buzz_sync<T>() -> Result<Fizzy<T, Shared>, T::Error>
+where
+    T: Copy + Rem<Output = T> + TryFrom<u8> + PartialEq + Display,
+{
+    Ok(Fizzy::new_sync()
+        .add_matcher(Matcher::divisible_by_sync(T::try_from(3)?, "fizz"))
+        .add_matcher(Matcher::divisible_by_sync(T::try_from(5)?, "buzz")))
+}
+
+fn divisors_out_of_range<T>() -> ! {
+    panic!(
+        "the divisors 3 and 5 do not fit in {}",
+        std::any::type_name::<T>()
+    )
+}
+
+/// FizzBuzz over zero-based positions: `period3` every third position and
+/// `period5` every fifth, both starting at position 0.
+pub fn fizzy_positional(period3: &str, period5: &str) -> Fizzy<usize> {
+    Fizzy::new()
+        .add_matcher(Matcher::new_periodic(3, 0, period3))
+        .add_matcher(Matcher::new_periodic(5, 0, period5))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple() {
+        let actual = fizz_buzz::<i32>().apply(1..=16).collect::<Vec<_>>();
+        let expected = [
+            "1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz", "buzz", "11", "fizz", "13",
+            "14", "fizzbuzz", "16",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn u8() {
+        let actual = fizz_buzz::<u8>().apply(1_u8..=16).collect::<Vec<_>>();
+        let expected = [
+            "1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz", "buzz", "11", "fizz", "13",
+            "14", "fizzbuzz", "16",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn u64() {
+        let actual = fizz_buzz::<u64>().apply(1_u64..=16).collect::<Vec<_>>();
+        let expected = [
+            "1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz", "buzz", "11", "fizz", "13",
+            "14", "fizzbuzz", "16",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn nonsequential() {
+        let collatz_12 = &[12, 6, 3, 10, 5, 16, 8, 4, 2, 1];
+        let actual = fizz_buzz::<i32>()
+            .apply(collatz_12.iter().cloned())
+            .collect::<Vec<_>>();
+        let expected = vec![
+            "fizz", "fizz", "fizz", "buzz", "buzz", "16", "8", "4", "2", "1",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn custom() {
+        let expected = vec![
+            "1", "2", "Fizz", "4", "Buzz", "Fizz", "Bam", "8", "Fizz", "Buzz", "11", "Fizz", "13",
+            "Bam", "BuzzFizz", "16",
+        ];
+        let fizzer: Fizzy<i32> = Fizzy::new()
+            .add_matcher(Matcher::new(|n: i32| n % 5 == 0, "Buzz"))
+            .add_matcher(Matcher::new(|n: i32| n % 3 == 0, "Fizz"))
+            .add_matcher(Matcher::new(|n: i32| n % 7 == 0, "Bam"));
+        let actual = fizzer.apply(1..=16).collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn f64() {
+        let actual = fizz_buzz::<f64>()
+            .apply(std::iter::successors(Some(1.0), |prev| Some(prev + 1.0)))
+            .take(16)
+            .collect::<Vec<_>>();
+        let expected = [
+            "1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz", "buzz", "11", "fizz", "13",
+            "14", "fizzbuzz", "16",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn minimal_generic_bounds() {
+        use std::fmt;
+        use std::ops::{Add, Rem};
+
+        #[derive(Clone, Copy, Debug, Default, PartialEq)]
+        struct Fizzable(u8);
+
+        impl From<u8> for Fizzable {
+            fn from(i: u8) -> Fizzable {
+                Fizzable(i)
+            }
+        }
+
+        impl fmt::Display for Fizzable {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let Fizzable(ref n) = self;
+                write!(f, "{n}")
+            }
+        }
+
+        impl Add for Fizzable {
+            type Output = Fizzable;
+            fn add(self, rhs: Fizzable) -> Fizzable {
+                let Fizzable(n1) = self;
+                let Fizzable(n2) = rhs;
+                Fizzable(n1 + n2)
+            }
+        }
+
+        impl Rem for Fizzable {
+            type Output = Fizzable;
+            fn rem(self, rhs: Fizzable) -> Fizzable {
+                let Fizzable(n1) = self;
+                let Fizzable(n2) = rhs;
+                Fizzable(n1 % n2)
+            }
+        }
 
         let actual = fizz_buzz::<Fizzable>()
             .apply(std::iter::successors(Some(Fizzable(1)), |prev| {
@@ -197,9 +2842,2064 @@ mod tests {
             .take(16)
             .collect::<Vec<_>>();
         let expected = [
-            "1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz", "buzz", "11", "fizz", "13",
-            "14", "fizzbuzz", "16",
+            "1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz", "buzz", "11", "fizz", "13",
+            "14", "fizzbuzz", "16",
+        ];
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn pad_left() {
+        let actual = fizz_buzz::<i32>()
+            .apply_pad(1..=15, 8, Align::Left)
+            .collect::<Vec<_>>();
+        assert_eq!(actual[0], "1       ");
+        assert_eq!(actual[2], "fizz    ");
+        assert_eq!(actual[14], "fizzbuzz");
+    }
+
+    #[test]
+    fn pad_right() {
+        let actual = fizz_buzz::<i32>()
+            .apply_pad(1..=15, 8, Align::Right)
+            .collect::<Vec<_>>();
+        assert_eq!(actual[0], "       1");
+        assert_eq!(actual[2], "    fizz");
+        assert_eq!(actual[14], "fizzbuzz");
+    }
+
+    #[test]
+    fn pad_center() {
+        let actual = fizz_buzz::<i32>()
+            .apply_pad(1..=15, 8, Align::Center)
+            .collect::<Vec<_>>();
+        assert_eq!(actual[0], "   1    ");
+        assert_eq!(actual[2], "  fizz  ");
+        assert_eq!(actual[14], "fizzbuzz");
+    }
+
+    #[test]
+    fn pad_with_fill_char() {
+        let actual = fizz_buzz::<i32>()
+            .apply_pad_with(1..=5, 6, Align::Right, '.')
+            .collect::<Vec<_>>();
+        assert_eq!(actual, [".....1", ".....2", "..fizz", ".....4", "..buzz"]);
+    }
+
+    #[test]
+    fn pad_narrower_than_label() {
+        let actual = fizz_buzz::<i32>()
+            .apply_pad(14..=15, 2, Align::Left)
+            .collect::<Vec<_>>();
+        assert_eq!(actual, ["14", "fizzbuzz"]);
+    }
+
+    fn messy() -> Fizzy<i32> {
+        Fizzy::new()
+            .add_matcher(Matcher::divisible_by(3, "fizz"))
+            .add_matcher(Matcher::divisible_by(3, "fizz"))
+            .add_matcher(Matcher::divisible_by(5, "buzz"))
+            .add_matcher(Matcher::divisible_by(5, ""))
+            .add_matcher(Matcher::divisible_by(5, "bang"))
+            .add_matcher(Matcher::new(|n: i32| n % 2 == 0, "even"))
+            .add_matcher(Matcher::new(|n: i32| n > 500, ""))
+            .add_matcher(Matcher::divisible_by(2, "even"))
+            .add_matcher(Matcher::divisible_by(4, "even"))
+            .add_matcher(Matcher::divisible_by(7, ""))
+    }
+
+    #[test]
+    fn normalize_report() {
+        let mut fizzer = messy();
+        let report = fizzer.normalize();
+        assert_eq!(
+            report,
+            NormalizeReport {
+                merged: vec![1, 4],
+                removed: vec![3, 9],
+            }
+        );
+        assert_eq!(fizzer.matchers.len(), 6);
+        assert_eq!(fizzer.matchers[0].substitution, "fizzfizz");
+        assert_eq!(fizzer.matchers[1].substitution, "buzzbang");
+    }
+
+    #[test]
+    fn normalize_preserves_output() {
+        let mut normalized = messy();
+        normalized.normalize();
+        let expected = messy().apply(1..=1000).collect::<Vec<_>>();
+        let actual = normalized.apply(1..=1000).collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn normalize_keeps_minus_one_rules_overflow_safe() {
+        let mut fizzer = Fizzy::new()
+            .add_matcher(parse_rule::<i64>("-1=a").unwrap())
+            .add_matcher(parse_rule::<i64>("-1=b").unwrap());
+        let report = fizzer.normalize();
+        assert_eq!(report.merged, [1]);
+        assert_eq!(fizzer.eval(i64::MIN), "ab");
+        assert_eq!(fizzer.eval(7), "ab");
+    }
+
+    #[test]
+    fn normalize_is_idempotent() {
+        let mut fizzer = messy();
+        fizzer.normalize();
+        assert_eq!(fizzer.normalize(), NormalizeReport::default());
+        let mut plain = fizz_buzz::<i32>();
+        assert_eq!(plain.normalize(), NormalizeReport::default());
+    }
+
+    #[test]
+    fn colorize() {
+        let colors = HashMap::from([("fizz".to_string(), "<g>"), ("buzz".to_string(), "<b>")]);
+        let actual = fizz_buzz::<i32>()
+            .apply_colorize(1..=15, colors)
+            .collect::<Vec<_>>();
+        assert_eq!(actual[0], "1");
+        assert_eq!(actual[2], "<g>fizz\x1b[0m");
+        assert_eq!(actual[4], "<b>buzz\x1b[0m");
+        assert_eq!(actual[14], "<g>fizz\x1b[0m<b>buzz\x1b[0m");
+    }
+
+    #[test]
+    fn colorize_unmapped_substitution() {
+        let colors = HashMap::from([("fizz".to_string(), "\x1b[32m")]);
+        let actual = fizz_buzz::<i32>()
+            .apply_colorize(4..=6, colors)
+            .collect::<Vec<_>>();
+        assert_eq!(actual, ["4", "buzz", "\x1b[32mfizz\x1b[0m"]);
+    }
+
+    #[test]
+    fn apply_slice() {
+        let values = (1..=16).collect::<Vec<i32>>();
+        let expected = fizz_buzz::<i32>().apply(1..=16).collect::<Vec<_>>();
+        assert_eq!(fizz_buzz::<i32>().apply_slice(&values), expected);
+    }
+
+    #[test]
+    fn markdown_table() {
+        let actual = fizz_buzz::<i32>().apply_markdown_table(1..=5);
+        let expected = "\
+| Value | Label |
+| ----- | ----- |
+| 1     | 1     |
+| 2     | 2     |
+| 3     | fizz  |
+| 4     | 4     |
+| 5     | buzz  |
+";
+        assert_eq!(actual, expected);
+        for line in actual.lines() {
+            assert!(line.starts_with("| ") && line.ends_with(" |"));
+            assert_eq!(line.matches(" | ").count(), 1);
+        }
+    }
+
+    #[test]
+    fn markdown_table_wide_and_escaped() {
+        let fizzer: Fizzy<i32> =
+            Fizzy::new().add_matcher(Matcher::new(|n: i32| n > 9, "a|long label"));
+        let actual = fizzer.apply_markdown_table(9..=10);
+        let expected = "\
+| Value | Label         |
+| ----- | ------------- |
+| 9     | 9             |
+| 10    | a\\|long label |
+";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn sample() {
+        let matcher = Matcher::divisible_by(3, "x");
+        assert_eq!(matcher.sample(1..=10), [3, 6, 9]);
+        assert_eq!(matcher.sample_count(1..=10), 3);
+    }
+
+    #[test]
+    fn sample_never_matching() {
+        let matcher = Matcher::new(|_: u64| false, "never");
+        assert!(matcher.sample(1..=100_000).is_empty());
+        assert_eq!(matcher.sample_count(1..=100_000), 0);
+    }
+
+    #[test]
+    fn to_ron() {
+        let actual = fizz_buzz::<i32>().apply_to_ron(1..=5);
+        assert_eq!(actual, r#"["1", "2", "fizz", "4", "buzz"]"#);
+        assert_eq!(fizz_buzz::<i32>().apply_to_ron(1..1), "[]");
+    }
+
+    #[test]
+    fn to_ron_escapes() {
+        let fizzer: Fizzy<i32> = Fizzy::new().add_matcher(Matcher::new(|_| true, "say \"hi\"\\\n"));
+        assert_eq!(fizzer.apply_to_ron(1..=1), r#"["say \"hi\"\\\n"]"#);
+    }
+
+    #[test]
+    fn to_ron_parses() {
+        let fizzer: Fizzy<i32> = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(2, "say \"hi\""))
+            .add_matcher(Matcher::divisible_by(3, "C:\\fizz\\"))
+            .add_matcher(Matcher::divisible_by(5, "bücher 🐝 größe"))
+            .add_matcher(Matcher::divisible_by(7, "tab\tbell\u{7}\n"));
+        let expected = fizzer.clone().apply(1..=210).collect::<Vec<_>>();
+        let parsed = ron::from_str::<Vec<String>>(&fizzer.apply_to_ron(1..=210)).unwrap();
+        assert_eq!(parsed, expected);
+        assert_eq!(
+            parsed[209],
+            "say \"hi\"C:\\fizz\\bücher 🐝 größetab\tbell\u{7}\n"
+        );
+    }
+
+    #[test]
+    fn apply_write() {
+        let mut out = Vec::new();
+        fizz_buzz::<i32>().apply_write(1..=5, &mut out).unwrap();
+        assert_eq!(out, b"1\n2\nfizz\n4\nbuzz\n");
+    }
+
+    #[test]
+    fn parse_rules() {
+        let matcher = parse_rule::<i64>("7=bam").unwrap();
+        assert_eq!(matcher.check(14), Some("bam".to_string()));
+        assert_eq!(matcher.check(15), None);
+        assert_eq!(
+            parse_rule::<i64>("7").err(),
+            Some(ParseRuleError::MissingSeparator("7".to_string()))
+        );
+        assert_eq!(
+            parse_rule::<i64>("x=bam").err(),
+            Some(ParseRuleError::InvalidDivisor("x".to_string()))
+        );
+        assert_eq!(
+            parse_rule::<i64>("0=bam").err(),
+            Some(ParseRuleError::ZeroDivisor)
+        );
+        assert_eq!(
+            parse_rule::<i64>("7=").err(),
+            Some(ParseRuleError::EmptyWord)
+        );
+    }
+
+    #[test]
+    fn to_toml() {
+        let actual = fizz_buzz::<i32>().apply_to_toml(1..=5);
+        assert_eq!(
+            actual,
+            "[fizzbuzz]\nlabels = [\"1\", \"2\", \"fizz\", \"4\", \"buzz\"]\n"
+        );
+    }
+
+    #[test]
+    fn to_toml_escapes() {
+        let fizzer: Fizzy<i32> = Fizzy::new().add_matcher(Matcher::new(|_| true, "a\"b\u{7}"));
+        assert_eq!(
+            fizzer.apply_to_toml(1..=1),
+            "[fizzbuzz]\nlabels = [\"a\\\"b\\u0007\"]\n"
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn to_toml_parses() {
+        #[derive(serde::Deserialize)]
+        struct Table {
+            labels: Vec<String>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Document {
+            fizzbuzz: Table,
+        }
+
+        let fizzer: Fizzy<i32> = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(2, "say \"hi\""))
+            .add_matcher(Matcher::divisible_by(3, "C:\\fizz\\"))
+            .add_matcher(Matcher::divisible_by(5, "tab\tbell\u{7}\nline"));
+        let expected = fizzer.clone().apply(1..=30).collect::<Vec<_>>();
+        let document: Document = toml::from_str(&fizzer.apply_to_toml(1..=30)).unwrap();
+        assert_eq!(document.fizzbuzz.labels, expected);
+        assert_eq!(
+            document.fizzbuzz.labels[29],
+            "say \"hi\"C:\\fizz\\tab\tbell\u{7}\nline"
+        );
+    }
+
+    #[test]
+    fn distinct_count() {
+        let (labels, distinct) = fizz_buzz::<i32>().apply_distinct_count(1..=15);
+        assert_eq!(labels.len(), 15);
+        assert_eq!(distinct, 11);
+        assert_eq!(
+            fizz_buzz::<i32>().apply_count_distinct_streaming(1..=15),
+            11
+        );
+        assert_eq!(fizz_buzz::<i32>().apply_distinct_count(1..1), (vec![], 0));
+    }
+
+    #[test]
+    fn first_match() {
+        assert_eq!(
+            fizz_buzz::<i32>().apply_first_match(1..=100),
+            Some((3, "fizz".to_string()))
+        );
+        assert_eq!(
+            fizz_buzz::<i32>().apply_first_match([1, 2, 4, 7].into_iter()),
+            None
+        );
+    }
+
+    #[test]
+    fn first_match_stops_early() {
+        let mut pulled = 0;
+        let iter = (1..).inspect(|_| pulled += 1);
+        assert_eq!(
+            fizz_buzz::<i32>().apply_first_match(iter),
+            Some((3, "fizz".to_string()))
+        );
+        assert_eq!(pulled, 3);
+    }
+
+    #[test]
+    fn last_match() {
+        assert_eq!(
+            fizz_buzz::<i32>().apply_last_match(1..=14),
+            Some((12, "fizz".to_string()))
+        );
+        assert_eq!(fizz_buzz::<i32>().apply_last_match(1..=2), None);
+    }
+
+    #[test]
+    fn limited_matcher() {
+        let fizzer: Fizzy<i32> =
+            Fizzy::new().add_matcher(Matcher::divisible_by(3, "fizz").limit(2));
+        let actual = fizzer.apply(1..=20).collect::<Vec<_>>();
+        assert_eq!(actual[2], "fizz");
+        assert_eq!(actual[5], "fizz");
+        assert_eq!(actual[8], "9");
+        assert_eq!(actual[17], "18");
+    }
+
+    #[test]
+    fn limited_matcher_resets_per_run() {
+        let fizzer = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(3, "fizz").limit(1))
+            .add_matcher(Matcher::divisible_by(5, "buzz"));
+        let values = (1..=15).collect::<Vec<i32>>();
+        let first = fizzer.apply_slice(&values);
+        assert_eq!(first[2], "fizz");
+        assert_eq!(first[5], "6");
+        assert_eq!(first[14], "buzz");
+        assert_eq!(fizzer.apply_slice(&values), first);
+    }
+
+    #[test]
+    fn normalize_keeps_limited_rules() {
+        let mut fizzer = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(3, "fizz"))
+            .add_matcher(Matcher::divisible_by(3, "fizz").limit(1))
+            .add_matcher(Matcher::divisible_by(3, "fizz"));
+        assert_eq!(fizzer.normalize(), NormalizeReport::default());
+        let actual = fizzer.apply(1..=6).collect::<Vec<_>>();
+        assert_eq!(actual, ["1", "2", "fizzfizzfizz", "4", "5", "fizzfizz"]);
+    }
+
+    #[test]
+    fn nth_match() {
+        assert_eq!(
+            fizz_buzz::<i32>().apply_nth_match(1..=100, 0),
+            Some((3, "fizz".to_string()))
+        );
+        assert_eq!(
+            fizz_buzz::<i32>().apply_nth_match(1..=100, 6),
+            Some((15, "fizzbuzz".to_string()))
+        );
+        assert_eq!(fizz_buzz::<i32>().apply_nth_match(1..=100, 999), None);
+    }
+
+    fn century() -> Fizzy<u32> {
+        let tens = Fizzy::new()
+            .add_matcher(Matcher::nested(|n: &u32| *n >= 10, |n| n / 10, fizz_buzz()).unwrap())
+            .add_matcher(Matcher::divisible_by(2, "even"));
+        Fizzy::new()
+            .add_matcher(Matcher::nested(|n: &u32| *n >= 100, |n| n / 100, tens).unwrap())
+            .add_matcher(Matcher::divisible_by(7, "bam"))
+    }
+
+    #[test]
+    fn nested() {
+        let fizzer = century();
+        assert_eq!(fizzer.eval(50), "50");
+        assert_eq!(fizzer.eval(98), "bam");
+        assert_eq!(fizzer.eval(3000), "fizzeven");
+        assert_eq!(fizzer.eval(700), "7bam");
+        assert_eq!(fizzer.eval(1500), "1");
+        assert_eq!(fizzer.eval(15000), "fizzbuzzeven");
+        assert_eq!(fizzer.eval(200), "even");
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let tens: Fizzy<u32> = Fizzy::new()
+            .add_matcher(Matcher::nested(|n: &u32| *n >= 10, |n| n / 10, fizz_buzz()).unwrap());
+        let err = Matcher::nested_with_limit(|n: &u32| *n >= 100, |n| n / 100, tens, 1)
+            .err()
+            .unwrap();
+        assert_eq!(err, NestingError { depth: 2, limit: 1 });
+        assert_eq!(century().depth(), 2);
+    }
+
+    #[test]
+    fn raw() {
+        let actual = fizz_buzz::<i32>().apply_raw(4..=6).collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            [
+                (4, None),
+                (5, Some("buzz".to_string())),
+                (6, Some("fizz".to_string()))
+            ]
+        );
+    }
+
+    #[test]
+    fn sparse() {
+        assert_eq!(fizz_buzz::<i32>().apply_sparse(1..=15).count(), 7);
+        assert_eq!(
+            fizz_buzz::<i32>().apply_sparse(1..=15).next(),
+            Some((3, "fizz".to_string()))
+        );
+    }
+
+    #[test]
+    fn over() {
+        let mut labels = Vec::new();
+        for label in fizz_buzz::<u32>().over(1..=5) {
+            labels.push(label);
+        }
+        assert_eq!(labels, ["1", "2", "fizz", "4", "buzz"]);
+    }
+
+    #[test]
+    fn over_clone() {
+        let bound = fizz_buzz::<u32>().over(1..=15);
+        let copy = bound.clone();
+        let expected = fizz_buzz::<u32>().apply(1..=15).collect::<Vec<_>>();
+        assert_eq!(bound.into_iter().collect::<Vec<_>>(), expected);
+        assert_eq!(copy.into_iter().collect::<Vec<_>>(), expected);
+    }
+
+    #[test]
+    fn over_into_parts() {
+        let (fizzer, source) = fizz_buzz::<u32>().over(vec![3, 5, 15]).into_parts();
+        assert_eq!(source.collect::<Vec<_>>(), [3, 5, 15]);
+        assert_eq!(fizzer.eval(15), "fizzbuzz");
+
+        let mut run = fizz_buzz::<u32>().over(1..=5).into_iter();
+        assert_eq!(run.next().as_deref(), Some("1"));
+        let (fizzer, rest) = run.into_parts();
+        assert_eq!(
+            fizzer.apply(rest).collect::<Vec<_>>(),
+            ["2", "fizz", "4", "buzz"]
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn bind_serializes_lazily() {
+        let fizzer = fizz_buzz::<i32>();
+        let labels = fizzer.apply_slice(&(1..=16).collect::<Vec<_>>());
+        let run = fizzer.bind(1..=16);
+        assert_eq!(
+            serde_json::to_string(&run).unwrap(),
+            serde_json::to_string(&labels).unwrap()
+        );
+        assert_eq!(serde_json::to_string(&run).unwrap(), "[]");
+
+        let pairs = (1..=16).zip(labels).collect::<Vec<_>>();
+        assert_eq!(
+            serde_json::to_string(&fizzer.bind(1..=16).pairs()).unwrap(),
+            serde_json::to_string(&pairs).unwrap()
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn bind_streams_unbounded_sources() {
+        struct Capped(usize);
+
+        impl Write for Capped {
+            fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+                if self.0 < buf.len() {
+                    return Err(io::Error::other("cap reached"));
+                }
+                self.0 -= buf.len();
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        // Collecting `1..` first would never finish; streaming stops at the cap.
+        let fizzer = fizz_buzz::<u64>();
+        let mut out = Capped(1 << 20);
+        assert!(serde_json::to_writer(&mut out, &fizzer.bind(1..)).is_err());
+        assert!(out.0 < 32);
+    }
+
+    #[test]
+    fn cloned_limit_counts_independently() {
+        let limited = Matcher::divisible_by(3, "fizz").limit(1);
+        assert_eq!(limited.check(3).as_deref(), Some("fizz"));
+        let copy = limited.clone();
+        assert_eq!(limited.check(6), None);
+        assert_eq!(copy.check(6), None);
+        copy.reset();
+        assert_eq!(copy.check(6).as_deref(), Some("fizz"));
+        assert_eq!(limited.check(9), None);
+    }
+
+    #[test]
+    fn count_matches_per_rule() {
+        let fizzer = fizz_buzz::<i32>();
+        assert_eq!(fizzer.apply_count_matches_per_rule(1..=15), [5, 3]);
+        assert_eq!(fizzer.apply_count_matches_per_rule(1..=15), [5, 3]);
+        assert_eq!(fizzer.apply_count_matches_per_rule(1..=30), [10, 6]);
+    }
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    struct Clamped(u8);
+
+    impl TryFrom<u8> for Clamped {
+        type Error = String;
+        fn try_from(i: u8) -> Result<Clamped, String> {
+            if i <= 3 {
+                Ok(Clamped(i))
+            } else {
+                Err(format!("{i} is out of range"))
+            }
+        }
+    }
+
+    impl std::fmt::Display for Clamped {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(f, "{}", self.0)
+        }
+    }
+
+    impl Rem for Clamped {
+        type Output = Clamped;
+        fn rem(self, rhs: Clamped) -> Clamped {
+            Clamped(self.0 % rhs.0)
+        }
+    }
+
+    #[test]
+    fn try_fizz_buzz_rejects_unrepresentable_divisors() {
+        let err = try_fizz_buzz::<Clamped>().err();
+        assert_eq!(err.as_deref(), Some("5 is out of range"));
+        let fizzer = try_fizz_buzz::<i32>().unwrap();
+        assert_eq!(fizzer.eval(15), "fizzbuzz");
+    }
+
+    #[test]
+    fn try_fizz_buzz_sync_rejects_unrepresentable_divisors() {
+        let err = try_fizz_buzz_sync::<Clamped>().err();
+        assert_eq!(err.as_deref(), Some("5 is out of range"));
+        let fizzer = try_fizz_buzz_sync::<i32>().unwrap();
+        assert_eq!(fizzer.eval(15), "fizzbuzz");
+    }
+
+    #[test]
+    #[should_panic(expected = "do not fit in")]
+    fn fizz_buzz_panics_on_unrepresentable_divisors() {
+        let _ = fizz_buzz::<Clamped>();
+    }
+
+    #[test]
+    #[should_panic(expected = "do not fit in")]
+    fn fizz_buzz_sync_panics_on_unrepresentable_divisors() {
+        let _ = fizz_buzz_sync::<Clamped>();
+    }
+
+    #[test]
+    fn parse_rule_with_try_from() {
+        let matcher = parse_rule::<i64>("3=fizz").unwrap();
+        assert!(matcher.matches(9));
+        assert_eq!(
+            parse_rule::<i8>("0=fizz").err(),
+            Some(ParseRuleError::ZeroDivisor)
+        );
+    }
+
+    #[test]
+    fn parse_rule_minus_one_does_not_overflow() {
+        let fizzer = Fizzy::new().add_matcher(parse_rule::<i64>("-1=x").unwrap());
+        assert_eq!(fizzer.eval(i64::MIN), "x");
+        assert_eq!(fizzer.apply_slice(&[i64::MIN; 9]), ["x"; 9]);
+        assert_eq!(fizzer.to_config().unwrap().rules[0].divisor, -1);
+        let matcher = parse_rule::<u8>("255=x").unwrap();
+        assert!(!matcher.matches(254));
+    }
+
+    #[test]
+    fn into_fn() {
+        let f = fizz_buzz::<i32>().into_fn();
+        assert_eq!(f(3), "fizz");
+        assert_eq!(f(15), "fizzbuzz");
+        assert_eq!(
+            (1..=5).map(&f).collect::<Vec<_>>(),
+            ["1", "2", "fizz", "4", "buzz"]
+        );
+    }
+
+    #[test]
+    fn display_over() {
+        let fizzer = fizz_buzz::<u32>();
+        let expected = fizz_buzz::<u32>()
+            .apply(1..=100)
+            .map(|label| label + "\n")
+            .collect::<String>();
+        assert_eq!(fizzer.display_over(1..=100).to_string(), expected);
+        assert_eq!(
+            fizzer.display_over(1..=5).terminator(", ").to_string(),
+            "1, 2, fizz, 4, buzz, "
+        );
+    }
+
+    #[test]
+    fn display_over_is_single_use_and_ignores_width() {
+        let fizzer = fizz_buzz::<u32>();
+        let adapter = fizzer.display_over(1..=3);
+        assert_eq!(format!("{adapter:>8}"), "1\n2\nfizz\n");
+        assert_eq!(adapter.to_string(), "");
+    }
+
+    #[test]
+    fn into_fn_ref() {
+        struct Labeler {
+            label: Box<dyn Fn(&u64) -> String>,
+        }
+
+        let mut labelers = HashMap::new();
+        labelers.insert(
+            "fizzbuzz",
+            Labeler {
+                label: fizz_buzz::<u64>().into_fn_ref(),
+            },
+        );
+        labelers.insert(
+            "even",
+            Labeler {
+                label: Fizzy::new()
+                    .add_matcher(Matcher::divisible_by(2, "even"))
+                    .into_fn_ref(),
+            },
+        );
+        let values = [3_u64, 4, 15];
+        let labels = |name: &str| values.iter().map(&labelers[name].label).collect::<Vec<_>>();
+        assert_eq!(labels("fizzbuzz"), ["fizz", "4", "fizzbuzz"]);
+        assert_eq!(labels("even"), ["3", "even", "15"]);
+    }
+
+    #[test]
+    fn config_round_trip() {
+        let original = fizz_buzz::<i64>()
+            .add_matcher(Matcher::divisible_by(7, "bam").limit(3))
+            .add_matcher(Matcher::divisible_by(3, ""));
+        let config = original.to_config().unwrap();
+        assert_eq!(
+            config.rules[2],
+            DivisorRule {
+                divisor: 7,
+                word: "bam".to_string(),
+                limit: Some(3),
+            }
+        );
+        let loaded = Fizzy::from_config(&config);
+        assert_eq!(
+            loaded.apply(-100..=1000).collect::<Vec<_>>(),
+            original.apply(-100..=1000).collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn config_toml_round_trip() {
+        let original = fizz_buzz::<i64>().add_matcher(Matcher::divisible_by(7, "bam").limit(3));
+        let toml = original.to_config().unwrap().to_toml_string().unwrap();
+        assert!(toml.contains("divisor = 7"));
+        let loaded = Fizzy::from_config(&RuleConfig::from_toml_str(&toml).unwrap());
+        assert_eq!(
+            loaded.apply(-100..=1000).collect::<Vec<_>>(),
+            original.apply(-100..=1000).collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn config_json_round_trip() {
+        let original = fizz_buzz::<i64>().add_matcher(Matcher::divisible_by(7, "bam").limit(3));
+        let json = original.to_config().unwrap().to_json_string().unwrap();
+        assert_eq!(
+            json,
+            r#"{"rules":[{"divisor":3,"word":"fizz","limit":null},{"divisor":5,"word":"buzz","limit":null},{"divisor":7,"word":"bam","limit":3}]}"#
+        );
+        let loaded = Fizzy::from_config(&RuleConfig::from_json_str(&json).unwrap());
+        assert_eq!(
+            loaded.apply(-100..=1000).collect::<Vec<_>>(),
+            original.apply(-100..=1000).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn config_round_trip_minus_one() {
+        let original = fizz_buzz::<i64>().add_matcher(parse_rule("-1=x").unwrap());
+        let loaded = Fizzy::from_config(&original.to_config().unwrap());
+        let probe = (i64::MIN..=i64::MIN + 100).chain(-100..=100);
+        assert_eq!(loaded.eval(i64::MIN), "x");
+        assert_eq!(
+            loaded.apply(probe.clone()).collect::<Vec<_>>(),
+            original.apply(probe).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn debug_shows_divisors() {
+        let fizzer = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(3, "fizz").limit(2))
+            .add_matcher(Matcher::new(|n: i64| n < 0, "neg"));
+        assert_eq!(
+            format!("{fizzer:?}"),
+            "Fizzy { matchers: [Matcher { divisor: 3, substitution: \"fizz\", limit: Some(2) }, \
+             Matcher { condition: <closure>, substitution: \"neg\", limit: None }] }"
+        );
+    }
+
+    #[test]
+    fn config_rejects_closures() {
+        let fizzer = Fizzy::new()
+            .add_matcher(Matcher::new(|n: i64| n < 0, "negative"))
+            .add_matcher(Matcher::divisible_by(3, "fizz"))
+            .add_matcher(Matcher::new(|n: i64| n > 100, "big"));
+        let err = fizzer.to_config().unwrap_err();
+        assert_eq!(err.opaque, [0, 2]);
+        assert_eq!(
+            err.to_string(),
+            "matchers at indices [0, 2] are closures and cannot be exported"
+        );
+    }
+
+    #[test]
+    fn chain_lazy() {
+        let actual = fizz_buzz::<i32>()
+            .apply_chain_lazy(1..=10, || -> std::ops::RangeInclusive<i32> {
+                panic!("should not be called")
+            })
+            .take(3)
+            .collect::<Vec<_>>();
+        assert_eq!(actual, ["1", "2", "fizz"]);
+
+        let actual = fizz_buzz::<i32>()
+            .apply_chain_lazy(1..=2, || vec![3, 5])
+            .collect::<Vec<_>>();
+        assert_eq!(actual, ["1", "2", "fizz", "buzz"]);
+    }
+
+    #[test]
+    fn fold_matches_next() {
+        let expected = fizz_buzz::<u32>().apply(1..=100).collect::<Vec<_>>();
+        let folded = fizz_buzz::<u32>()
+            .apply(1..=100)
+            .fold(Vec::new(), |mut labels, label| {
+                labels.push(label);
+                labels
+            });
+        assert_eq!(folded, expected);
+
+        let mut total = 0;
+        fizz_buzz::<u32>()
+            .apply(1..=100)
+            .for_each(|label| total += label.len());
+        assert_eq!(total, expected.iter().map(String::len).sum::<usize>());
+    }
+
+    #[test]
+    fn short_circuit_then_next() {
+        let mut run = fizz_buzz::<u32>().apply(1..=20);
+        assert_eq!(run.find(|label| label == "buzz").as_deref(), Some("buzz"));
+        assert_eq!(run.next().as_deref(), Some("fizz"));
+        assert!(run.any(|label| label == "fizzbuzz"));
+        assert_eq!(run.next().as_deref(), Some("16"));
+        let rest = run.fold(String::new(), |acc, label| acc + &label + " ");
+        assert_eq!(rest, "17 fizz 19 buzz ");
+    }
+
+    #[test]
+    fn short_circuit_keeps_limit_state() {
+        let fizzer = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(3u32, "fizz").limit(3))
+            .add_matcher(Matcher::divisible_by(5, "buzz"));
+        let mut run = fizzer.apply(1..=30);
+        assert_eq!(run.position(|label| label == "fizz"), Some(2));
+        assert_eq!(run.next().as_deref(), Some("4"));
+        assert!(!run.all(|label| label != "fizz"));
+        assert_eq!(run.next().as_deref(), Some("7"));
+        // The third and last "fizz" is 9; later multiples of 3 fall back.
+        assert_eq!(
+            run.find(|label| label.starts_with('1')).as_deref(),
+            Some("11")
+        );
+        let rest = run.collect::<Vec<_>>();
+        assert_eq!(rest[..4], ["12", "13", "14", "buzz"]);
+        assert_eq!(rest.iter().filter(|label| *label == "fizz").count(), 0);
+    }
+
+    #[test]
+    fn chunks_parallel() {
+        let expected = fizz_buzz::<u64>().apply(1..=100_000).collect::<Vec<_>>();
+        let actual = fizz_buzz_sync::<u64>().apply_by_chunks_parallel(1..=100_000, 1_000, 4);
+        assert_eq!(actual, expected);
+        let actual = fizz_buzz_sync::<u64>().apply_by_chunks_parallel(1..=100_000, 7, 3);
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn chunks_parallel_edge_cases() {
+        assert!(fizz_buzz_sync::<u64>()
+            .apply_by_chunks_parallel(1..1, 4, 4)
+            .is_empty());
+        assert_eq!(
+            fizz_buzz_sync::<u64>().apply_by_chunks_parallel(1..=5, 100, 8),
+            ["1", "2", "fizz", "4", "buzz"]
+        );
+    }
+
+    #[test]
+    fn chunks_parallel_shares_limits() {
+        let fizzer =
+            Fizzy::new_sync().add_matcher(Matcher::divisible_by_sync(3u64, "fizz").limit(10));
+        let actual = fizzer.apply_by_chunks_parallel(1..=1_000, 10, 4);
+        assert_eq!(actual.iter().filter(|label| *label == "fizz").count(), 10);
+    }
+
+    #[test]
+    fn chunks_parallel_opaque_rules() {
+        let fizzer = fizz_buzz_sync::<u64>().add_matcher(Matcher::new_sync(|n: u64| n > 90, "big"));
+        let actual = fizzer.apply_by_chunks_parallel(1..=100, 3, 4);
+        assert_eq!(actual[89], "fizzbuzz");
+        assert_eq!(actual[98], "fizzbig");
+    }
+
+    #[test]
+    fn local_matchers_capture_rc() {
+        let seen = std::rc::Rc::new(std::cell::Cell::new(0));
+        let counter = std::rc::Rc::clone(&seen);
+        let fizzer = Fizzy::new().add_matcher(Matcher::new(
+            move |n: u32| {
+                counter.set(counter.get() + 1);
+                n.is_multiple_of(2)
+            },
+            "even",
+        ));
+        assert_eq!(
+            fizzer.apply(1..=4).collect::<Vec<_>>(),
+            ["1", "even", "3", "even"]
+        );
+        assert_eq!(seen.get(), 4);
+    }
+
+    #[test]
+    fn json_lines() {
+        let actual = fizz_buzz::<i32>()
+            .apply_json_lines(1..=5)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            actual,
+            ["\"1\"\n", "\"2\"\n", "\"fizz\"\n", "\"4\"\n", "\"buzz\"\n"]
+        );
+        let joined = actual.concat();
+        assert_eq!(joined.lines().count(), 5);
+        assert!(joined
+            .lines()
+            .all(|line| line.starts_with('"') && line.ends_with('"')));
+    }
+
+    #[test]
+    fn json_lines_escapes() {
+        let fizzer: Fizzy<i32> =
+            Fizzy::new().add_matcher(Matcher::new(|_| true, "a\\b\"c\nd\u{1}"));
+        assert_eq!(
+            fizzer.apply_json_lines(1..=1).collect::<Vec<_>>(),
+            ["\"a\\\\b\\\"c\\nd\\u0001\"\n"]
+        );
+    }
+
+    #[test]
+    fn ssv() {
+        assert_eq!(fizz_buzz::<i32>().apply_ssv(1..=5), "1 2 fizz 4 buzz");
+        let fizzer: Fizzy<i32> = Fizzy::new().add_matcher(Matcher::divisible_by(2, "fizz buzz"));
+        assert_eq!(fizzer.apply_ssv(1..=3), "1 \"fizz buzz\" 3");
+    }
+
+    #[test]
+    fn tsv() {
+        let actual = fizz_buzz::<i32>().apply_tsv(13..=16);
+        assert_eq!(actual, "13\t14\tfizzbuzz\t16");
+        assert!(!actual.contains("fizz\tbuzz"));
+        let fizzer: Fizzy<i32> = Fizzy::new().add_matcher(Matcher::divisible_by(2, "a b\tc"));
+        assert_eq!(fizzer.apply_tsv(1..=2), "1\t\"a b\\tc\"");
+    }
+
+    #[test]
+    fn html_list() {
+        let expected = "\
+<ol>
+  <li>1</li>
+  <li>2</li>
+  <li>fizz</li>
+  <li>4</li>
+  <li>buzz</li>
+</ol>
+";
+        assert_eq!(
+            fizz_buzz::<i32>().apply_html_list(1..=5, ListKind::Ordered),
+            expected
+        );
+        let unordered = fizz_buzz::<i32>().apply_html_list(1..=1, ListKind::Unordered);
+        assert_eq!(unordered, "<ul>\n  <li>1</li>\n</ul>\n");
+    }
+
+    #[test]
+    fn html_list_escapes() {
+        let fizzer: Fizzy<i32> = Fizzy::new().add_matcher(Matcher::divisible_by(2, "<b>&\"x\""));
+        let actual = fizzer.apply_html_list_with_class(1..=2, ListKind::Ordered, "a\"b");
+        assert_eq!(
+            actual,
+            "<ol class=\"a&quot;b\">\n  <li>1</li>\n  <li>&lt;b&gt;&amp;&quot;x&quot;</li>\n</ol>\n"
+        );
+    }
+
+    #[test]
+    fn ascii_table() {
+        let actual = fizz_buzz::<i32>().apply_ascii_table(10..=15);
+        let expected = "\
++---+----------+
+| # | label    |
++---+----------+
+| 0 | buzz     |
+| 1 | 11       |
+| 2 | fizz     |
+| 3 | 13       |
+| 4 | 14       |
+| 5 | fizzbuzz |
++---+----------+
+";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn ascii_table_widths() {
+        let actual = fizz_buzz::<i32>().apply_ascii_table(1..=11);
+        let lines = actual.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "+----+-------+");
+        assert_eq!(lines[3], "|  0 | 1     |");
+        assert_eq!(lines[13], "| 10 | 11    |");
+        assert!(lines.iter().all(|line| line.len() == lines[0].len()));
+    }
+
+    #[cfg(feature = "unicode-table")]
+    #[test]
+    fn unicode_table() {
+        let actual = fizz_buzz::<i32>().apply_unicode_table(3..=3);
+        let expected = "\
+╒═══╤═══════╕
+│ # │ label │
+╞═══╪═══════╡
+│ 0 │ fizz  │
+╘═══╧═══════╛
+";
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn frequencies() {
+        let frequencies = fizz_buzz::<i32>().apply_frequencies(1..=30);
+        assert_eq!(frequencies["fizz"], 8);
+        assert_eq!(frequencies["buzz"], 4);
+        assert_eq!(frequencies["fizzbuzz"], 2);
+        assert_eq!(frequencies["1"], 1);
+        assert_eq!(frequencies.len(), 19);
+    }
+
+    #[test]
+    fn histogram() {
+        let actual = fizz_buzz::<i32>().apply_histogram(1..=30);
+        let lines = actual.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 19);
+        assert_eq!(lines[0], format!("fizz     | {} | 8", "#".repeat(40)));
+        assert_eq!(lines[1], format!("buzz     | {:<40} | 4", "#".repeat(20)));
+        assert_eq!(lines[2], format!("fizzbuzz | {:<40} | 2", "#".repeat(10)));
+        assert_eq!(lines[3], format!("1        | {:<40} | 1", "#".repeat(5)));
+        assert!(lines[4].starts_with("11 "));
+    }
+
+    #[test]
+    fn histogram_with_width() {
+        let actual = fizz_buzz::<i32>().apply_histogram_with_width(1..=6, 4);
+        assert_eq!(
+            actual,
+            "fizz | #### | 2\n1    | ##   | 1\n2    | ##   | 1\n4    | ##   | 1\nbuzz | ##   | 1\n"
+        );
+        assert_eq!(fizz_buzz::<i32>().apply_histogram(1..1), "");
+    }
+
+    #[test]
+    fn compose() {
+        let matcher: Matcher<i64> = Matcher::divisible_by(3_i32, "fizz").compose(|v: i64| v as i32);
+        assert_eq!(matcher.check(9_i64).as_deref(), Some("fizz"));
+        assert_eq!(matcher.check(10_i64), None);
+        assert_eq!(matcher.sample(1..=10_i64), [3, 6, 9]);
+
+        let closure = Matcher::new(|n: i32| n > 2, "big").compose(|s: &str| s.len() as i32);
+        assert!(closure.matches("abc"));
+        assert!(!closure.matches("ab"));
+    }
+
+    #[test]
+    fn compose_keeps_limit() {
+        let matcher = Matcher::divisible_by(3_i32, "fizz")
+            .limit(1)
+            .compose(|v: i64| v as i32);
+        assert_eq!(matcher.check(3).as_deref(), Some("fizz"));
+        assert_eq!(matcher.check(6), None);
+    }
+
+    #[test]
+    fn contramap() {
+        let fizzer: Fizzy<i64> = fizz_buzz::<u32>().contramap(|v: i64| v.unsigned_abs() as u32);
+        assert_eq!(fizzer.eval(-15), "fizzbuzz");
+        let actual = fizzer.apply(-6..=-1).collect::<Vec<_>>();
+        assert_eq!(actual, ["fizz", "buzz", "-4", "fizz", "-2", "-1"]);
+    }
+
+    #[test]
+    fn label_changes() {
+        let actual = fizz_buzz::<i32>()
+            .apply_enumerate_with_label_change(1..=5)
+            .collect::<Vec<_>>();
+        assert_eq!(actual[0], (0, "1".to_string(), false));
+        assert_eq!(actual[2], (2, "fizz".to_string(), true));
+        assert_eq!(actual[3], (3, "4".to_string(), true));
+        assert_eq!(actual[4], (4, "buzz".to_string(), true));
+
+        let fizzer: Fizzy<i32> = Fizzy::new().add_matcher(Matcher::new(|n: i32| n < 3, "low"));
+        let changes = fizzer
+            .apply_enumerate_with_label_change(1..=4)
+            .map(|(_, _, changed)| changed)
+            .collect::<Vec<_>>();
+        assert_eq!(changes, [false, false, true, true]);
+    }
+
+    #[test]
+    fn running_distinct() {
+        let actual = fizz_buzz::<i32>()
+            .apply_running_distinct(1..=20)
+            .collect::<Vec<_>>();
+        assert_eq!(actual[2], ("fizz".to_string(), true));
+        for index in [5, 8, 11] {
+            assert_eq!(actual[index], ("fizz".to_string(), false));
+        }
+        assert_eq!(actual[0], ("1".to_string(), true));
+        assert_eq!(actual.iter().filter(|(_, first)| *first).count(), 14);
+    }
+
+    #[test]
+    fn label_hash() {
+        let hashes = fizz_buzz::<i32>()
+            .apply_label_hash(1..=1000)
+            .collect::<Vec<_>>();
+        assert_eq!(hashes[2], ("fizz".to_string(), 0xaa930e78eff6b6ec));
+        let mut by_hash = HashMap::new();
+        for (label, hash) in &hashes {
+            assert_eq!(by_hash.entry(*hash).or_insert(label), &label);
+        }
+        assert_eq!(by_hash.len(), 536);
+    }
+
+    #[test]
+    fn chunk_stats() {
+        let stats = fizz_buzz::<i32>()
+            .apply_chunk_stats(1..=15, 5)
+            .collect::<Vec<_>>();
+        assert_eq!(stats.len(), 3);
+        assert_eq!(
+            stats[0],
+            ChunkStats {
+                chunk_index: 0,
+                match_count: 2,
+                unique_labels: 5,
+                most_common_label: Some("fizz".to_string()),
+            }
+        );
+        assert_eq!(stats[1].match_count, 3);
+        assert_eq!(stats[1].unique_labels, 4);
+        assert_eq!(stats[1].most_common_label.as_deref(), Some("fizz"));
+        assert_eq!(stats[2].chunk_index, 2);
+        assert_eq!(stats[2].most_common_label.as_deref(), Some("fizz"));
+    }
+
+    #[test]
+    fn chunk_stats_partial_and_unmatched() {
+        let stats = fizz_buzz::<i32>()
+            .apply_chunk_stats([1, 2, 4, 7].into_iter(), 3)
+            .collect::<Vec<_>>();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].match_count, 0);
+        assert_eq!(stats[0].most_common_label, None);
+        assert_eq!(stats[1].unique_labels, 1);
+    }
+
+    #[test]
+    fn label_entropy() {
+        let actual = fizz_buzz::<i32>().apply_label_entropy(1..=30);
+        assert!((actual - 3.773557).abs() < 0.001);
+        assert_eq!(fizz_buzz::<i32>().apply_label_entropy(1..1), 0.0);
+    }
+
+    #[test]
+    fn label_entropy_is_maximal_when_uniform() {
+        let uniform = Fizzy::<i32>::new().apply_label_entropy(1..=30);
+        assert!((uniform - 30f64.log2()).abs() < 1e-9);
+        assert!(fizz_buzz::<i32>().apply_label_entropy(1..=30) < uniform);
+        let constant: Fizzy<i32> = Fizzy::new().add_matcher(Matcher::new(|_| true, "x"));
+        assert_eq!(constant.apply_label_entropy(1..=30), 0.0);
+    }
+
+    #[test]
+    fn nth() {
+        assert_eq!(
+            fizz_buzz::<i32>().apply_nth(1..=100, 0).as_deref(),
+            Some("1")
+        );
+        assert_eq!(
+            fizz_buzz::<i32>().apply_nth(1..=100, 14).as_deref(),
+            Some("fizzbuzz")
+        );
+        assert_eq!(fizz_buzz::<i32>().apply_nth(1..=100, 1000), None);
+    }
+
+    #[test]
+    fn any_all_none() {
+        assert!(!fizz_buzz::<i32>().apply_any(1..=2));
+        assert!(fizz_buzz::<i32>().apply_any(1..=3));
+        assert!(fizz_buzz::<i32>().apply_all(3..=3));
+        assert!(!fizz_buzz::<i32>().apply_all(3..=4));
+        assert!(fizz_buzz::<i32>().apply_none(1..=2));
+        assert!(!fizz_buzz::<i32>().apply_none(1..=3));
+    }
+
+    #[test]
+    fn count_chars_and_bytes() {
+        let chars = fizz_buzz::<i32>().apply_count_chars(1..=15);
+        assert_eq!(chars, fizz_buzz::<i32>().apply_count_bytes(1..=15));
+        assert_eq!(chars, 5 + 3 * 2 + 4 * 4 + 2 * 4 + 8);
+
+        let arrow = || Fizzy::new().add_matcher(Matcher::divisible_by(3, "fizz→"));
+        assert_eq!(arrow().apply_count_chars(3..=3), 5);
+        assert_eq!(arrow().apply_count_bytes(3..=3), 7);
+    }
+
+    #[test]
+    fn position_of() {
+        assert_eq!(
+            fizz_buzz::<i32>().apply_position_of(1..=30, "fizz"),
+            [2, 5, 8, 11, 17, 20, 23, 26]
+        );
+        assert_eq!(
+            fizz_buzz::<i32>().apply_first_position_of(1.., "fizzbuzz"),
+            Some(14)
+        );
+        assert_eq!(
+            fizz_buzz::<i32>().apply_first_position_of(1..=10, "bang"),
+            None
+        );
+    }
+
+    #[test]
+    fn cumulative_match_rate() {
+        let rates: Vec<f64> = fizz_buzz::<i32>()
+            .apply_cumulative_match_rate(1..=15)
+            .collect();
+        assert_eq!(rates.len(), 15);
+        assert_eq!(rates[0], 0.0);
+        assert!((rates[2] - 1.0 / 3.0).abs() < 1e-12);
+        assert!((rates[14] - 7.0 / 15.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn with_source_index() {
+        let labelled: Vec<(usize, String)> = fizz_buzz::<i32>()
+            .apply_with_source_index([(5, 15), (1, 3), (2, 5)])
+            .collect();
+        assert_eq!(
+            labelled,
+            [
+                (5, "fizzbuzz".to_string()),
+                (1, "fizz".to_string()),
+                (2, "buzz".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn multiline() {
+        let grid = fizz_buzz::<i32>().apply_multiline(1..=15, 5);
+        let rows: Vec<&str> = grid.lines().collect();
+        assert_eq!(rows.len(), 3);
+        assert!(rows.iter().all(|row| row.split_whitespace().count() == 5));
+        assert_eq!(rows[0], "1        2        fizz     4        buzz");
+
+        let grid = fizz_buzz::<i32>().apply_multiline(1..=15, 4);
+        let counts: Vec<usize> = grid
+            .lines()
+            .map(|row| row.split_whitespace().count())
+            .collect();
+        assert_eq!(counts, [4, 4, 4, 3]);
+        assert_eq!(grid.lines().last(), Some("13       14       fizzbuzz"));
+    }
+
+    #[test]
+    fn then_zip() {
+        let pairs: Vec<(String, &str)> = fizz_buzz::<i32>()
+            .apply_then_zip(1..=5, ["a", "b", "c", "d", "e"])
+            .collect();
+        assert_eq!(pairs.len(), 5);
+        assert_eq!(pairs[2], ("fizz".to_string(), "c"));
+        assert_eq!(pairs[4], ("buzz".to_string(), "e"));
+    }
+
+    #[test]
+    fn label_ngrams() {
+        let bigrams: Vec<Vec<String>> = fizz_buzz::<i32>().apply_label_ngrams(1..=5, 2).collect();
+        assert_eq!(
+            bigrams,
+            [["1", "2"], ["2", "fizz"], ["fizz", "4"], ["4", "buzz"]]
+        );
+        assert_eq!(fizz_buzz::<i32>().apply_label_ngrams(1..=5, 3).count(), 3);
+        assert_eq!(fizz_buzz::<i32>().apply_label_ngrams(1..=5, 6).count(), 0);
+    }
+
+    #[test]
+    fn label_transitions() {
+        let transitions: Vec<(String, String)> =
+            fizz_buzz::<i32>().apply_label_transitions(1..=5).collect();
+        let expected = [("1", "2"), ("2", "fizz"), ("fizz", "4"), ("4", "buzz")]
+            .map(|(a, b)| (a.to_string(), b.to_string()));
+        assert_eq!(transitions, expected);
+        assert_eq!(fizz_buzz::<i32>().apply_label_transitions(1..=1).count(), 0);
+    }
+
+    #[test]
+    fn label_runs() {
+        let runs: Vec<(String, usize)> = fizz_buzz::<i32>().apply_label_runs(1..=9).collect();
+        let expected = ["1", "2", "fizz", "4", "buzz", "fizz", "7", "8", "fizz"]
+            .map(|label| (label.to_string(), 1));
+        assert_eq!(runs, expected);
+
+        let low = Fizzy::new().add_matcher(Matcher::new(|n: i32| n < 3, "low"));
+        let runs: Vec<(String, usize)> =
+            low.apply_label_runs([0, 1, 2, 3, 3].into_iter()).collect();
+        assert_eq!(runs, [("low".to_string(), 3), ("3".to_string(), 2)]);
+    }
+
+    #[test]
+    fn label_most_and_least_common() {
+        assert_eq!(
+            fizz_buzz::<i32>().apply_label_most_common(1..=300),
+            Some(("fizz".to_string(), 80))
+        );
+        assert_eq!(
+            fizz_buzz::<i32>().apply_label_least_common(1..=300),
+            Some(("1".to_string(), 1))
+        );
+        assert_eq!(
+            fizz_buzz::<i32>().apply_label_most_common(1..=2),
+            Some(("1".to_string(), 1))
+        );
+        assert_eq!(fizz_buzz::<i32>().apply_label_most_common(1..1), None);
+    }
+
+    #[test]
+    fn label_median_length() {
+        // Sorted lengths: 1 x5, 2 x3, 4 x6, 8.
+        assert_eq!(fizz_buzz::<i32>().apply_label_median_length(1..=15), 2.0);
+        // 1, 1, 4, 1, 4, 4 -> 1, 1, 1, 4, 4, 4.
+        assert_eq!(fizz_buzz::<i32>().apply_label_median_length(1..=6), 2.5);
+        assert!(fizz_buzz::<i32>().apply_label_median_length(1..1).is_nan());
+    }
+
+    #[test]
+    fn label_variance() {
+        let fizz = || Fizzy::new().add_matcher(Matcher::new(|_: i32| true, "fizz"));
+        assert_eq!(fizz().apply_label_variance(1..=10), 0.0);
+        assert_eq!(fizz().apply_label_stddev(1..=10), 0.0);
+        assert_eq!(fizz_buzz::<i32>().apply_label_variance(1..=1), 0.0);
+
+        // Lengths sum to 43 and their squares to 177: (177 - 43^2 / 15) / 14.
+        let expected = (177.0 - 43.0 * 43.0 / 15.0) / 14.0;
+        let variance = fizz_buzz::<i32>().apply_label_variance(1..=15);
+        assert!((variance - expected).abs() < 1e-9);
+        let stddev = fizz_buzz::<i32>().apply_label_stddev(1..=15);
+        assert!((stddev - expected.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn label_jaccard() {
+        assert_eq!(fizz_buzz::<i32>().apply_label_jaccard(1..=15, 1..=15), 1.0);
+        // {1, 2, fizz} against {1, 2, fizz, 4, buzz}.
+        assert_eq!(fizz_buzz::<i32>().apply_label_jaccard(1..=3, 1..=5), 0.6);
+        assert_eq!(fizz_buzz::<i32>().apply_label_jaccard(1..=2, 4..=4), 0.0);
+        assert_eq!(fizz_buzz::<i32>().apply_label_jaccard(1..1, 1..1), 1.0);
+    }
+
+    #[test]
+    fn label_cosine_sim() {
+        let similarity = fizz_buzz::<i32>().apply_label_cosine_sim(1..=15, 1..=15);
+        assert!((similarity - 1.0).abs() < 1e-12);
+
+        let split = || {
+            Fizzy::new()
+                .add_matcher(Matcher::new(|n: i32| n < 10, "low"))
+                .add_matcher(Matcher::new(|n: i32| n >= 10, "high"))
+        };
+        assert_eq!(split().apply_label_cosine_sim(1..=5, 20..=25), 0.0);
+        // (3, 0) against (3, 4).
+        let similarity = split().apply_label_cosine_sim(1..=3, 7..=13);
+        assert!((similarity - 0.6).abs() < 1e-12);
+        assert_eq!(split().apply_label_cosine_sim(1..1, 1..=3), 0.0);
+    }
+
+    #[test]
+    fn label_edit_distance() {
+        assert_eq!(
+            fizz_buzz::<i32>().apply_label_edit_distance(1..=5, 1..=5),
+            0
+        );
+        assert_eq!(fizz_buzz::<i32>().apply_label_edit_distance(1..=5, 1..1), 5);
+        // "1 2 fizz" -> "2 fizz 4": drop the head, append a tail.
+        assert_eq!(
+            fizz_buzz::<i32>().apply_label_edit_distance(1..=3, 2..=4),
+            2
+        );
+
+        // 7, 14 and 21 each turn into a different label.
+        let base = fizz_buzz::<i32>().collect_labels(1..=21);
+        let extended = fizz_buzz::<i32>()
+            .add_matcher(Matcher::divisible_by(7, "bang"))
+            .collect_labels(1..=21);
+        assert_eq!(edit_distance(&base, &extended, None), 3);
+    }
+
+    #[test]
+    fn label_edit_distance_banded() {
+        for (x, y) in [(1..=30, 1..=30), (1..=30, 4..=33), (1..=10, 1..=25)] {
+            let exact = fizz_buzz::<i32>().apply_label_edit_distance(x.clone(), y.clone());
+            for band in [0, 1, 3, 40] {
+                let banded =
+                    fizz_buzz::<i32>().apply_label_edit_distance_banded(x.clone(), y.clone(), band);
+                assert!(banded >= exact);
+                if exact <= band {
+                    assert_eq!(banded, exact);
+                }
+            }
+        }
+        let a: Vec<char> = "kitten".chars().collect();
+        let b: Vec<char> = "sitting".chars().collect();
+        assert_eq!(edit_distance(&a, &b, None), 3);
+        assert_eq!(edit_distance(&a, &b, Some(1)), 3);
+    }
+
+    #[test]
+    fn label_zip_match_rate() {
+        assert_eq!(
+            fizz_buzz::<i32>().apply_label_zip_match_rate(fizz_buzz(), 1..=100),
+            1.0
+        );
+        let bang = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(3, "fizz"))
+            .add_matcher(Matcher::divisible_by(5, "bang"));
+        // Only the 20 multiples of 5 differ.
+        assert_eq!(
+            fizz_buzz::<i32>().apply_label_zip_match_rate(bang, 1..=100),
+            0.8
+        );
+        assert_eq!(
+            fizz_buzz::<i32>().apply_label_zip_match_rate(Fizzy::new(), 1..1),
+            1.0
+        );
+    }
+
+    #[test]
+    fn moving_average_label_len() {
+        // Lengths 1, 1, 4, 1, 4, 4.
+        let averages: Vec<f64> = fizz_buzz::<i32>()
+            .apply_moving_average_label_len(1..=6, 3)
+            .collect();
+        assert_eq!(averages, [1.0, 1.0, 2.0, 2.0, 3.0, 3.0]);
+        let averages: Vec<f64> = fizz_buzz::<i32>()
+            .apply_moving_average_label_len(1..=3, 1)
+            .collect();
+        assert_eq!(averages, [1.0, 1.0, 4.0]);
+    }
+
+    #[test]
+    fn label_pairs() {
+        let pairs = fizz_buzz::<i32>().apply_label_pairs_sorted(1..=9);
+        assert_eq!(pairs.len(), 8);
+        assert!(pairs.windows(2).all(|w| w[0] < w[1]));
+        let fizz_four = ("fizz".to_string(), "4".to_string());
+        assert_eq!(pairs.iter().filter(|pair| **pair == fizz_four).count(), 1);
+
+        let frequencies = fizz_buzz::<i32>().apply_label_pair_frequencies(1..=9);
+        assert_eq!(frequencies[&fizz_four], 1);
+        assert_eq!(frequencies.values().sum::<usize>(), 8);
+
+        // Every label is "x", so the one distinct pair repeats.
+        let x = || Fizzy::new().add_matcher(Matcher::new(|_: i32| true, "x"));
+        assert_eq!(x().apply_label_pairs_sorted(1..=5).len(), 1);
+        assert_eq!(
+            x().apply_label_pair_frequencies(1..=5)
+                .values()
+                .sum::<usize>(),
+            4
+        );
+    }
+
+    #[test]
+    fn periodic() {
+        let fizz = Matcher::new_periodic(3, 0, "fizz");
+        let hits: Vec<usize> = (0..10).filter(|&pos| fizz.matches(pos)).collect();
+        assert_eq!(hits, [0, 3, 6, 9]);
+
+        let late = Matcher::new_periodic(4, 5, "x");
+        let hits: Vec<usize> = (0..15).filter(|&pos| late.matches(pos)).collect();
+        assert_eq!(hits, [5, 9, 13]);
+
+        let labels: Vec<String> = fizzy_positional("a", "b").apply(0..6).collect();
+        assert_eq!(labels, ["ab", "1", "2", "a", "4", "b"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "period must be positive")]
+    fn periodic_rejects_zero_period() {
+        Matcher::new_periodic(0, 0, "never");
+    }
+
+    #[test]
+    fn label_prefix_tree() {
+        let trie = fizz_buzz::<i32>().apply_label_prefix_tree(1..=15);
+        assert!(trie.contains("fizz"));
+        assert!(trie.contains("fizzbuzz"));
+        assert_eq!(trie.starts_with("fi"), ["fizz", "fizzbuzz"]);
+        assert_eq!(trie.starts_with("1"), ["1", "11", "13", "14"]);
+        // 8 distinct numbers plus fizz, buzz and fizzbuzz.
+        assert_eq!(trie.len(), 11);
+    }
+
+    #[test]
+    fn label_sorted_merge() {
+        // "1" "8" "buzz" "fizzbuzz" and "2" "buzz" "fizz".
+        let merged = fizz_buzz::<i32>()
+            .apply_label_sorted_merge([1, 8, 10, 15].into_iter(), [2, 5, 3].into_iter());
+        assert_eq!(merged, ["1", "2", "8", "buzz", "buzz", "fizz", "fizzbuzz"]);
+        assert!(merged.windows(2).all(|w| w[0] <= w[1]));
+
+        let merged = fizz_buzz::<i32>().apply_label_sorted_merge(1..1, [4, 3].into_iter());
+        assert_eq!(merged, ["4", "fizz"]);
+    }
+
+    #[test]
+    fn label_to_digit_sum() {
+        let parsed: Vec<(String, Option<u64>)> =
+            fizz_buzz::<i32>().apply_label_to_digit_sum(3..=4).collect();
+        assert_eq!(
+            parsed,
+            [("fizz".to_string(), None), ("4".to_string(), Some(4))]
+        );
+
+        let sums: Vec<(String, Option<u64>)> = fizz_buzz::<i32>()
+            .apply_label_digit_sum([98, 99, 100, 101].into_iter())
+            .collect();
+        assert_eq!(
+            sums,
+            [
+                ("98".to_string(), Some(17)),
+                ("fizz".to_string(), None),
+                ("buzz".to_string(), None),
+                ("101".to_string(), Some(2))
+            ]
+        );
+        let plain = Fizzy::new().add_matcher(Matcher::new(|_: u64| false, "never"));
+        let sums: Vec<Option<u64>> = plain
+            .apply_label_digit_sum(100..=100)
+            .map(|(_, sum)| sum)
+            .collect();
+        assert_eq!(sums, [Some(1)]);
+    }
+
+    #[test]
+    fn label_base_convert() {
+        let binary: Vec<String> = fizz_buzz::<u64>()
+            .apply_label_base_convert(1..=5, 2)
+            .collect();
+        assert_eq!(binary, ["1", "10", "fizz", "100", "buzz"]);
+        let hex: Vec<String> = fizz_buzz::<u64>()
+            .apply_label_base_convert([11, 0, 254].into_iter(), 16)
+            .collect();
+        assert_eq!(hex, ["b", "fizzbuzz", "fe"]);
+        assert_eq!(to_base(0, 7), "0");
+        assert_eq!(to_base(u64::MAX, 36), "3w5e11264sgsf");
+    }
+
+    #[test]
+    #[should_panic(expected = "base must be in 2..=36")]
+    fn label_base_convert_rejects_base() {
+        let _ = fizz_buzz::<u64>().apply_label_base_convert(1..=5, 37);
+    }
+
+    #[test]
+    fn label_rot13() {
+        let encoded: Vec<String> = fizz_buzz::<i32>().apply_label_rot13(13..=15).collect();
+        assert_eq!(encoded, ["13", "14", "svmmohmm"]);
+        let labels: Vec<String> = fizz_buzz::<i32>().apply(1..=30).collect();
+        let round_trip: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_rot13(1..=30)
+            .map(|label| transform::rot13(&label))
+            .collect();
+        assert_eq!(round_trip, labels);
+    }
+
+    #[test]
+    fn label_caesar() {
+        let shifted: Vec<String> = fizz_buzz::<i32>().apply_label_caesar(3..=5, 1).collect();
+        assert_eq!(shifted, ["gjaa", "4", "cvaa"]);
+        let identity: Vec<String> = fizz_buzz::<i32>().apply_label_caesar(1..=15, 0).collect();
+        assert_eq!(
+            identity,
+            fizz_buzz::<i32>().apply(1..=15).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn label_reverse_str() {
+        let reversed: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_reverse_str(13..=15)
+            .collect();
+        assert_eq!(reversed, ["31", "41", "zzubzzif"]);
+        let arrow = Fizzy::new().add_matcher(Matcher::divisible_by(3, "fizz→"));
+        let reversed: Vec<String> = arrow.apply_label_reverse_str(3..=3).collect();
+        assert_eq!(reversed, ["→zzif"]);
+    }
+
+    #[test]
+    fn label_reverse_words() {
+        let spaced = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(3, "fizz "))
+            .add_matcher(Matcher::divisible_by(5, "buzz"));
+        let reversed: Vec<String> = spaced.apply_label_reverse_words(13..=15).collect();
+        assert_eq!(reversed, ["13", "14", "buzz fizz"]);
+    }
+
+    #[test]
+    fn label_hash_color() {
+        let colored: Vec<(String, String)> =
+            fizz_buzz::<i32>().apply_label_hash_color(3..=6).collect();
+        assert_eq!(colored[0].0, "fizz");
+        assert_eq!(colored[0].1, colored[3].1);
+        assert_eq!(colored[0].1, "\x1b[34mfizz\x1b[0m");
+        assert_eq!(colored[2].1, "\x1b[37mbuzz\x1b[0m");
+    }
+
+    #[test]
+    fn label_soundex() {
+        let codes: Vec<(String, String)> = fizz_buzz::<i32>()
+            .apply_label_soundex([4, 3, 5, 15].into_iter())
+            .collect();
+        let expected = [
+            ("4", ""),
+            ("fizz", "F200"),
+            ("buzz", "B200"),
+            ("fizzbuzz", "F212"),
+        ]
+        .map(|(label, code)| (label.to_string(), code.to_string()));
+        assert_eq!(codes, expected);
+
+        let names = Fizzy::new().add_matcher(Matcher::divisible_by(2, "Robert"));
+        let codes: Vec<String> = names
+            .apply_label_soundex(2..=2)
+            .map(|(_, code)| code)
+            .collect();
+        assert_eq!(codes, ["R163"]);
+    }
+
+    #[test]
+    fn label_metaphone() {
+        let codes: Vec<(String, String)> = fizz_buzz::<i32>()
+            .apply_label_metaphone([3, 5, 15, 7].into_iter())
+            .collect();
+        let expected = [
+            ("fizz", "FS"),
+            ("buzz", "BS"),
+            ("fizzbuzz", "FSBS"),
+            ("7", ""),
+        ]
+        .map(|(label, code)| (label.to_string(), code.to_string()));
+        assert_eq!(codes, expected);
+    }
+
+    #[test]
+    fn label_word_count() {
+        let spaced = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(3, "fizz buzz wham"))
+            .add_matcher(Matcher::divisible_by(5, " "));
+        let counts: Vec<(String, usize)> = spaced.apply_label_word_count(3..=5).collect();
+        let expected = [("fizz buzz wham", 3), ("4", 1), (" ", 0)]
+            .map(|(label, words)| (label.to_string(), words));
+        assert_eq!(counts, expected);
+    }
+
+    #[test]
+    fn label_char_histogram() {
+        let histograms: Vec<(String, HashMap<char, usize>)> = fizz_buzz::<i32>()
+            .apply_label_char_histogram(15..=15)
+            .collect();
+        let (label, histogram) = &histograms[0];
+        assert_eq!(label, "fizzbuzz");
+        assert_eq!(histogram.len(), 5);
+        assert_eq!(histogram[&'z'], 4);
+        assert_eq!(histogram[&'f'], 1);
+    }
+
+    #[test]
+    fn label_is_palindrome() {
+        let flags: Vec<(String, bool)> = fizz_buzz::<i32>()
+            .apply_label_is_palindrome([3, 5, 11].into_iter())
+            .collect();
+        let expected =
+            [("fizz", false), ("buzz", false), ("11", true)].map(|(l, b)| (l.to_string(), b));
+        assert_eq!(flags, expected);
+
+        let palindromes = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(3, "aba"))
+            .add_matcher(Matcher::divisible_by(5, "été"));
+        let flags: Vec<bool> = palindromes
+            .apply_label_is_palindrome(3..=5)
+            .map(|(_, flag)| flag)
+            .collect();
+        assert_eq!(flags, [true, true, true]);
+    }
+
+    #[test]
+    fn label_is_pangram() {
+        let pangram = Fizzy::new().add_matcher(Matcher::divisible_by(
+            2,
+            "The quick brown fox jumps over the lazy dog",
+        ));
+        let flags: Vec<bool> = pangram
+            .apply_label_is_pangram(1..=2)
+            .map(|(_, flag)| flag)
+            .collect();
+        assert_eq!(flags, [false, true]);
+    }
+
+    #[test]
+    fn label_is_anagram_of() {
+        let flags: Vec<bool> = fizz_buzz::<i32>()
+            .apply_label_is_anagram_of([3, 5, 6].into_iter(), "zizf")
+            .map(|(_, flag)| flag)
+            .collect();
+        assert_eq!(flags, [true, false, true]);
+    }
+
+    #[test]
+    fn label_levenshtein_to() {
+        let distances: Vec<(String, usize)> = fizz_buzz::<i32>()
+            .apply_label_levenshtein_to([3, 5, 15, 7].into_iter(), "fizz")
+            .collect();
+        let expected = [("fizz", 0), ("buzz", 2), ("fizzbuzz", 4), ("7", 4)]
+            .map(|(label, distance)| (label.to_string(), distance));
+        assert_eq!(distances, expected);
+    }
+
+    #[test]
+    fn label_longest_common_prefix() {
+        assert_eq!(
+            fizz_buzz::<i32>().apply_label_longest_common_prefix(1..=15),
+            ""
+        );
+        assert_eq!(
+            fizz_buzz::<i32>().apply_label_longest_common_prefix(1..1),
+            ""
+        );
+        assert_eq!(
+            fizz_buzz::<i32>().apply_label_longest_common_prefix([3, 6, 9].into_iter()),
+            "fizz"
+        );
+        let prefixed = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(1, "pre"))
+            .add_matcher(Matcher::divisible_by(2, "fix"))
+            .add_matcher(Matcher::divisible_by(3, "view"));
+        assert_eq!(prefixed.apply_label_longest_common_prefix(1..=6), "pre");
+    }
+
+    #[test]
+    fn label_format_number() {
+        let values = [999, 1000, 1000000];
+        let us: Vec<String> = fizz_buzz::<i64>()
+            .apply_label_format_number(values.into_iter(), NumberLocale::Us)
+            .collect();
+        assert_eq!(us, ["fizz", "buzz", "buzz"]);
+
+        let plain = Fizzy::new().add_matcher(Matcher::divisible_by(7, "wham"));
+        let cases = [
+            (NumberLocale::Us, ["999", "1,000", "1,000,000"]),
+            (NumberLocale::European, ["999", "1.000", "1.000.000"]),
+            (NumberLocale::Indian, ["999", "1,000", "10,00,000"]),
         ];
-        assert_eq!(actual, expected);
+        for (locale, expected) in cases {
+            let formatted: Vec<String> = plain
+                .clone()
+                .apply_label_format_number(values.into_iter(), locale)
+                .collect();
+            assert_eq!(formatted, expected);
+        }
+        let passthrough: Vec<String> = plain
+            .apply_label_format_number([7, 1002].into_iter(), NumberLocale::European)
+            .collect();
+        assert_eq!(passthrough, ["wham", "1.002"]);
+    }
+
+    #[test]
+    fn label_emoji_encode() {
+        let map = HashMap::from([("fizz", "🎵"), ("buzz", "🐝")]);
+        let encoded: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_emoji_encode(13..=15, &map)
+            .collect();
+        assert_eq!(encoded, ["13", "14", "fizzbuzz"]);
+        let encoded: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_emoji_encode(3..=5, &map)
+            .collect();
+        assert_eq!(encoded, ["🎵", "4", "🐝"]);
+        assert_eq!(encoded[0].len(), 4);
+        assert_eq!(encoded[0].chars().count(), 1);
+    }
+
+    #[test]
+    fn label_emoji_default() {
+        let encoded: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_emoji_default(1..=15)
+            .collect();
+        assert_eq!(encoded[0], "1");
+        assert_eq!(encoded[2], "🎵");
+        assert_eq!(encoded[4], "🐝");
+        assert_eq!(encoded[14], "🎶");
+    }
+
+    #[test]
+    fn label_url_encode() {
+        let encoded: Vec<String> = fizz_buzz::<i32>().apply_label_url_encode(3..=5).collect();
+        assert_eq!(encoded, ["fizz", "4", "buzz"]);
+
+        let spaced = Fizzy::new().add_matcher(Matcher::divisible_by(2, "fizz buzz"));
+        let encoded: Vec<String> = spaced.apply_label_url_encode(1..=2).collect();
+        assert_eq!(encoded, ["1", "fizz%20buzz"]);
+        assert_eq!(
+            encode::percent_decode(&encoded[1]).as_deref(),
+            Some("fizz buzz")
+        );
+    }
+
+    #[test]
+    fn label_html_encode() {
+        let tagged = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(3, "<fizz>"))
+            .add_matcher(Matcher::divisible_by(5, "'buzz' & \"co\""));
+        let encoded: Vec<String> = tagged.apply_label_html_encode(3..=5).collect();
+        assert_eq!(
+            encoded,
+            ["&lt;fizz&gt;", "4", "&#x27;buzz&#x27; &amp; &quot;co&quot;"]
+        );
+        let plain: Vec<String> = fizz_buzz::<i32>().apply_label_html_encode(1..=15).collect();
+        assert_eq!(plain, fizz_buzz::<i32>().apply(1..=15).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn label_morse() {
+        let encoded: Vec<String> = fizz_buzz::<i32>().apply_label_morse(3..=5).collect();
+        assert_eq!(
+            encoded,
+            ["··-· ·· --·· --··", "····-", "-··· ··- --·· --··"]
+        );
+
+        let pulses: Vec<(String, usize)> = fizz_buzz::<i32>()
+            .apply_label_morse_audio_pulse_count([3, 5, 15, 10].into_iter())
+            .collect();
+        let expected = [("fizz", 14), ("buzz", 15), ("fizzbuzz", 29), ("buzz", 15)]
+            .map(|(label, n)| (label.to_string(), n));
+        assert_eq!(pulses, expected);
+    }
+
+    #[test]
+    fn label_binary() {
+        let encoded: Vec<String> = fizz_buzz::<i32>().apply_label_binary(3..=5).collect();
+        assert_eq!(
+            encoded,
+            [
+                "01100110 01101001 01111010 01111010",
+                "00110100",
+                "01100010 01110101 01111010 01111010",
+            ]
+        );
+    }
+
+    #[test]
+    fn label_base64() {
+        let encoded: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_base64([3, 5, 15].into_iter())
+            .collect();
+        assert_eq!(encoded, ["Zml6eg==", "YnV6eg==", "Zml6emJ1eno="]);
+
+        let decoded: Vec<Result<String, encode::DecodeError>> = fizz_buzz::<i32>()
+            .apply_label_base64_decode(encoded.iter())
+            .collect();
+        assert_eq!(
+            decoded,
+            [Ok("fizz".into()), Ok("buzz".into()), Ok("fizzbuzz".into())]
+        );
+
+        let bad: Vec<Result<String, encode::DecodeError>> =
+            encode::base64_decode_labels(["4", "/w=="].into_iter()).collect();
+        assert_eq!(
+            bad,
+            [
+                Err(encode::DecodeError::InvalidLength(1)),
+                Err(encode::DecodeError::InvalidUtf8)
+            ]
+        );
+    }
+
+    #[test]
+    fn label_capitalize() {
+        let titled: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_capitalize([3, 5, 15, 7].into_iter())
+            .collect();
+        assert_eq!(titled, ["Fizz", "Buzz", "Fizzbuzz", "7"]);
+
+        let custom = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(2, "fIZZ bUZZ"))
+            .add_matcher(Matcher::divisible_by(3, "über"));
+        let titled: Vec<String> = custom.apply_label_capitalize(2..=3).collect();
+        assert_eq!(titled, ["Fizz Buzz", "Über"]);
+    }
+
+    #[test]
+    fn label_upper_lower() {
+        let custom = Fizzy::new().add_matcher(Matcher::divisible_by(2, "Fizz Über"));
+        let upper: Vec<String> = custom.clone().apply_label_upper(1..=2).collect();
+        assert_eq!(upper, ["1", "FIZZ ÜBER"]);
+        let lower: Vec<String> = custom.apply_label_lower(1..=2).collect();
+        assert_eq!(lower, ["1", "fizz über"]);
+    }
+
+    #[test]
+    fn label_slugify() {
+        let slugs: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_slugify([3, 5, 15].into_iter())
+            .collect();
+        assert_eq!(slugs, ["fizz", "buzz", "fizzbuzz"]);
+
+        let custom = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(2, "Fizz Buzz, Wham!"))
+            .add_matcher(Matcher::divisible_by(3, "?!"));
+        let slugs: Vec<String> = custom.apply_label_slugify(1..=3).collect();
+        assert_eq!(slugs, ["1", "fizz-buzz-wham", ""]);
+    }
+
+    #[test]
+    fn label_truncate_words() {
+        let wordy = Fizzy::new().add_matcher(Matcher::divisible_by(2, "fizz buzz  wham"));
+        let two: Vec<String> = wordy.clone().apply_label_truncate_words(1..=2, 2).collect();
+        assert_eq!(two, ["1", "fizz buzz"]);
+        let one: Vec<String> = wordy.clone().apply_label_truncate_words(2..=2, 1).collect();
+        assert_eq!(one, ["fizz"]);
+        let all: Vec<String> = wordy.clone().apply_label_truncate_words(2..=2, 3).collect();
+        assert_eq!(all, ["fizz buzz  wham"]);
+        let zero: Vec<String> = wordy.apply_label_truncate_words(1..=2, 0).collect();
+        assert_eq!(zero, ["", ""]);
+
+        let single: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_truncate_words(3..=5, 1)
+            .collect();
+        assert_eq!(single, ["fizz", "4", "buzz"]);
+    }
+
+    #[test]
+    fn label_abbreviate() {
+        let wordy = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(2, "fizz"))
+            .add_matcher(Matcher::divisible_by(3, " buzz"))
+            .add_matcher(Matcher::divisible_by(5, " wham"));
+        let initials: Vec<String> = wordy
+            .clone()
+            .apply_label_abbreviate([2, 6, 30, 7].into_iter())
+            .collect();
+        assert_eq!(initials, ["F", "FB", "FBW", "7"]);
+        let lower: Vec<String> = wordy.apply_label_abbreviate_lower(6..=6).collect();
+        assert_eq!(lower, ["fb"]);
+
+        let blank = Fizzy::new().add_matcher(Matcher::divisible_by(2, " "));
+        let initials: Vec<String> = blank.apply_label_abbreviate(2..=2).collect();
+        assert_eq!(initials, [""]);
+    }
+
+    #[test]
+    fn label_repeat() {
+        let doubled: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_repeat([3, 5, 15, 4].into_iter(), 2)
+            .collect();
+        assert_eq!(doubled, ["fizzfizz", "buzzbuzz", "fizzbuzzfizzbuzz", "44"]);
+        let once: Vec<String> = fizz_buzz::<i32>().apply_label_repeat(1..=15, 1).collect();
+        assert_eq!(once, fizz_buzz::<i32>().apply(1..=15).collect::<Vec<_>>());
+        let none: Vec<String> = fizz_buzz::<i32>().apply_label_repeat(2..=3, 0).collect();
+        assert_eq!(none, ["", ""]);
+    }
+
+    #[test]
+    fn label_interleave_chars() {
+        let dotted: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_interleave_chars(3..=5, '.')
+            .collect();
+        assert_eq!(dotted, ["f.i.z.z", "4", "b.u.z.z"]);
+        let spaced: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_interleave_chars(15..=15, ' ')
+            .collect();
+        assert_eq!(spaced, ["f i z z b u z z"]);
+
+        for fill in ['.', ' ', '🔥'] {
+            for (label, interleaved) in fizz_buzz::<i32>()
+                .apply(1..=15)
+                .zip(fizz_buzz::<i32>().apply_label_interleave_chars(1..=15, fill))
+            {
+                let chars = label.chars().count();
+                assert_eq!(interleaved.chars().count(), 2 * chars - 1);
+            }
+        }
+    }
+
+    #[test]
+    fn label_wrap_width() {
+        let label = "fizz buzz wham bangs";
+        assert_eq!(label.chars().count(), 20);
+        let long = Fizzy::new().add_matcher(Matcher::divisible_by(2, label));
+        let wrapped: Vec<String> = long.apply_label_wrap_width(1..=2, 10).collect();
+        assert_eq!(wrapped[0], "1");
+        let lines: Vec<&str> = wrapped[1].lines().collect();
+        assert_eq!(lines, ["fizz buzz", "wham bangs"]);
+        assert!(lines.iter().all(|line| line.chars().count() <= 10));
+        assert_eq!(lines.join(" "), label);
+
+        let unchanged: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_wrap_width(1..=15, 10)
+            .collect();
+        assert_eq!(
+            unchanged,
+            fizz_buzz::<i32>().apply(1..=15).collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "flate2")]
+    #[test]
+    fn label_zlib_round_trip() {
+        let long = "fizzbuzz".repeat(10000);
+        let fizzy = Fizzy::new().add_matcher(Matcher::divisible_by(2, &long));
+        let compressed: Vec<Vec<u8>> = fizzy.apply_label_zlib_compress(1..=2).collect();
+        assert!(compressed[1].len() < long.len());
+
+        let decompressed: Vec<String> = fizz_buzz::<i32>()
+            .apply_label_zlib_decompress(compressed.into_iter())
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(decompressed, ["1".to_string(), long]);
+
+        let corrupt: Vec<io::Result<String>> =
+            compress::zlib_decompress_labels([b"fizz".to_vec()].into_iter()).collect();
+        assert!(corrupt[0].is_err());
+    }
+
+    #[test]
+    fn label_lzw_round_trip() {
+        let compressed: Vec<Vec<u16>> = fizz_buzz::<i32>()
+            .apply_label_lzw_compress(1..=15)
+            .collect();
+        let decompressed: Vec<Option<String>> = fizz_buzz::<i32>()
+            .apply_label_lzw_decompress(compressed.into_iter())
+            .collect();
+        let expected: Vec<Option<String>> = fizz_buzz::<i32>().apply(1..=15).map(Some).collect();
+        assert_eq!(decompressed, expected);
+
+        let repeated = Fizzy::new().add_matcher(Matcher::divisible_by(2, "fizzbuzzfizzbuzz"));
+        let codes: Vec<Vec<u16>> = repeated.apply_label_lzw_compress(2..=4).collect();
+        assert!(codes[0].len() < "fizzbuzzfizzbuzz".len());
+        // Each label starts from a fresh dictionary, so equal labels encode equally.
+        assert_eq!(codes[0], codes[2]);
+    }
+
+    #[test]
+    fn fizz_buzz_accepts_borrowing_values() {
+        #[derive(Clone, Copy, PartialEq)]
+        struct Length<'a> {
+            n: u32,
+            unit: &'a str,
+        }
+
+        impl Rem for Length<'_> {
+            type Output = Self;
+
+            fn rem(self, rhs: Self) -> Self {
+                Length {
+                    n: self.n % rhs.n,
+                    unit: "",
+                }
+            }
+        }
+
+        impl From<u8> for Length<'_> {
+            fn from(n: u8) -> Self {
+                Length {
+                    n: n.into(),
+                    unit: "",
+                }
+            }
+        }
+
+        impl Display for Length<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{}{}", self.n, self.unit)
+            }
+        }
+
+        let unit = String::from("m");
+        let lengths = (1..=5).map(|n| Length { n, unit: &unit });
+        let labels: Vec<String> = fizz_buzz().apply(lengths).collect();
+        assert_eq!(labels, ["1m", "2m", "fizz", "4m", "buzz"]);
     }
 }
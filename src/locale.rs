@@ -0,0 +1,89 @@
+/// Digit grouping conventions for [`format_integer`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NumberLocale {
+    /// Groups of three separated by `,`: `1,000,000`.
+    Us,
+    /// Groups of three separated by `.`: `1.000.000`.
+    European,
+    /// The last three digits, then groups of two, separated by `,`:
+    /// `10,00,000`.
+    Indian,
+}
+
+impl NumberLocale {
+    pub fn thousands_separator(self) -> char {
+        match self {
+            NumberLocale::Us | NumberLocale::Indian => ',',
+            NumberLocale::European => '.',
+        }
+    }
+
+    pub fn decimal_separator(self) -> char {
+        match self {
+            NumberLocale::Us | NumberLocale::Indian => '.',
+            NumberLocale::European => ',',
+        }
+    }
+}
+
+/// Writes `n` in decimal with `locale`'s digit grouping.
+pub fn format_integer(n: i64, locale: NumberLocale) -> String {
+    let digits = n.unsigned_abs().to_string();
+    let separator = locale.thousands_separator();
+    // Group widths counted from the right; only the first differs by locale.
+    let rest = match locale {
+        NumberLocale::Us | NumberLocale::European => 3,
+        NumberLocale::Indian => 2,
+    };
+    let mut groups = Vec::new();
+    let mut end = digits.len();
+    let mut width = 3;
+    while end > width {
+        groups.push(&digits[end - width..end]);
+        end -= width;
+        width = rest;
+    }
+    groups.push(&digits[..end]);
+
+    let mut formatted = String::with_capacity(digits.len() + groups.len());
+    if n < 0 {
+        formatted.push('-');
+    }
+    for (i, group) in groups.iter().rev().enumerate() {
+        if i > 0 {
+            formatted.push(separator);
+        }
+        formatted.push_str(group);
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grouping() {
+        let cases = [
+            (999, ["999", "999", "999"]),
+            (1000, ["1,000", "1.000", "1,000"]),
+            (1000000, ["1,000,000", "1.000.000", "10,00,000"]),
+            (-1234567, ["-1,234,567", "-1.234.567", "-12,34,567"]),
+            (0, ["0", "0", "0"]),
+        ];
+        let locales = [
+            NumberLocale::Us,
+            NumberLocale::European,
+            NumberLocale::Indian,
+        ];
+        for (n, expected) in cases {
+            for (locale, expected) in locales.into_iter().zip(expected) {
+                assert_eq!(format_integer(n, locale), expected, "{n} in {locale:?}");
+            }
+        }
+        assert_eq!(
+            format_integer(i64::MIN, NumberLocale::Us),
+            "-9,223,372,036,854,775,808"
+        );
+    }
+}
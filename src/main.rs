@@ -1,3 +1,287 @@
-fn main() {
-    println!("Hello, world!");
+use std::cell::RefCell;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::{self, BufRead, Write};
+use std::process::ExitCode;
+
+use fizzbuzz::{fizz_buzz, parse_rule, Fizzy};
+
+const USAGE: &str = "usage: fizzbuzz [--rule DIVISOR=WORD]... [--stdin [--lenient] | MAX]";
+
+struct Options {
+    fizzy: Fizzy<i64>,
+    stdin: bool,
+    lenient: bool,
+    max: i64,
+}
+
+fn parse_args<I>(args: I) -> Result<Options, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut rules = Vec::new();
+    let mut stdin = false;
+    let mut lenient = false;
+    let mut max = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--stdin" => stdin = true,
+            "--lenient" => lenient = true,
+            "--rule" => {
+                let rule = args.next().ok_or("--rule needs a DIVISOR=WORD argument")?;
+                rules.push(parse_rule(&rule).map_err(|e| e.to_string())?);
+            }
+            _ if max.is_none() && !arg.starts_with("--") => {
+                max = Some(arg.parse().map_err(|_| format!("invalid MAX {arg:?}"))?);
+            }
+            _ => return Err(format!("unexpected argument {arg:?}")),
+        }
+    }
+    if lenient && !stdin {
+        return Err("--lenient only applies with --stdin".to_string());
+    }
+    if stdin && max.is_some() {
+        return Err("MAX cannot be combined with --stdin".to_string());
+    }
+    let fizzy = if rules.is_empty() {
+        fizz_buzz()
+    } else {
+        rules.into_iter().fold(Fizzy::new(), Fizzy::add_matcher)
+    };
+    Ok(Options {
+        fizzy,
+        stdin,
+        lenient,
+        max: max.unwrap_or(100),
+    })
+}
+
+#[derive(Debug)]
+enum LineError {
+    Io(io::Error),
+    Parse { line: usize, text: String },
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LineError::Io(err) => write!(f, "{err}"),
+            LineError::Parse { line, text } => {
+                write!(f, "line {line}: not an integer: {text:?}")
+            }
+        }
+    }
+}
+
+impl Error for LineError {}
+
+impl From<io::Error> for LineError {
+    fn from(err: io::Error) -> LineError {
+        LineError::Io(err)
+    }
+}
+
+/// Writes to the output shared by the labels and the echoed invalid lines.
+struct SharedOut<'a, W>(&'a RefCell<W>);
+
+impl<W: Write> Write for SharedOut<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.borrow_mut().flush()
+    }
+}
+
+/// Reads up to the next integer line. Output so far is flushed before
+/// blocking on input, so interactive use sees each label immediately.
+fn next_value<L, W, E>(
+    lines: &mut L,
+    out: &RefCell<W>,
+    err: &mut E,
+    lenient: bool,
+) -> Result<Option<i64>, LineError>
+where
+    L: Iterator<Item = (usize, io::Result<String>)>,
+    W: Write,
+    E: Write,
+{
+    out.borrow_mut().flush()?;
+    for (index, line) in lines {
+        let line = line?;
+        let text = line.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match text.parse::<i64>() {
+            Ok(value) => return Ok(Some(value)),
+            Err(_) if lenient => {
+                writeln!(err, "warning: line {}: not an integer: {text:?}", index + 1)?;
+                let mut out = out.borrow_mut();
+                writeln!(out, "{line}")?;
+                out.flush()?;
+            }
+            Err(_) => {
+                return Err(LineError::Parse {
+                    line: index + 1,
+                    text: text.to_string(),
+                })
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// All lines are one run, so limits count across the whole input.
+fn process_lines<R, W, E>(
+    fizzy: &Fizzy<i64>,
+    input: R,
+    out: W,
+    mut err: E,
+    lenient: bool,
+) -> Result<(), LineError>
+where
+    R: BufRead,
+    W: Write,
+    E: Write,
+{
+    let out = RefCell::new(out);
+    let mut lines = input.lines().enumerate();
+    let mut failure = None;
+    let values = std::iter::from_fn(|| match next_value(&mut lines, &out, &mut err, lenient) {
+        Ok(value) => value,
+        Err(error) => {
+            failure = Some(error);
+            None
+        }
+    });
+    fizzy.apply_write(values, SharedOut(&out))?;
+    out.borrow_mut().flush()?;
+    failure.map_or(Ok(()), Err)
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("error: {message}\n{USAGE}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let result = if options.stdin {
+        process_lines(
+            &options.fizzy,
+            io::stdin().lock(),
+            io::stdout().lock(),
+            io::stderr().lock(),
+            options.lenient,
+        )
+    } else {
+        options
+            .fizzy
+            .apply_write(1..=options.max, io::stdout().lock())
+            .map_err(LineError::from)
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(LineError::Io(err)) if err.kind() == io::ErrorKind::BrokenPipe => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fizzbuzz::Matcher;
+
+    fn run(input: &str, lenient: bool) -> (Result<(), LineError>, String, String) {
+        let fizzy = fizz_buzz::<i64>();
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        let result = process_lines(&fizzy, input.as_bytes(), &mut out, &mut err, lenient);
+        (
+            result,
+            String::from_utf8(out).unwrap(),
+            String::from_utf8(err).unwrap(),
+        )
+    }
+
+    #[test]
+    fn annotates_lines() {
+        let (result, out, err) = run("3\n\n10\n  15 \n7\n", false);
+        assert!(result.is_ok());
+        assert_eq!(out, "fizz\nbuzz\nfizzbuzz\n7\n");
+        assert_eq!(err, "");
+    }
+
+    #[test]
+    fn parse_error_aborts_with_line_number() {
+        let (result, out, _) = run("3\n\nabc\n5\n", false);
+        match result {
+            Err(LineError::Parse { line, text }) => {
+                assert_eq!(line, 3);
+                assert_eq!(text, "abc");
+            }
+            other => panic!("expected a parse error, got {other:?}"),
+        }
+        assert_eq!(out, "fizz\n");
+    }
+
+    #[test]
+    fn lenient_echoes_invalid_lines() {
+        let (result, out, err) = run("1\nx1\n3\n4.5\n5\n", true);
+        assert!(result.is_ok());
+        assert_eq!(out, "1\nx1\nfizz\n4.5\nbuzz\n");
+        assert_eq!(
+            err,
+            "warning: line 2: not an integer: \"x1\"\nwarning: line 4: not an integer: \"4.5\"\n"
+        );
+    }
+
+    #[test]
+    fn limits_span_all_lines() {
+        let fizzy = Fizzy::new().add_matcher(Matcher::divisible_by(3, "fizz").limit(1));
+        let mut out = Vec::new();
+        let result = process_lines(
+            &fizzy,
+            "3\nx\n6\n9\n".as_bytes(),
+            &mut out,
+            io::sink(),
+            true,
+        );
+        assert!(result.is_ok());
+        assert_eq!(String::from_utf8(out).unwrap(), "fizz\nx\n6\n9\n");
+    }
+
+    #[test]
+    fn minus_one_divisor_accepts_minimum() {
+        let options = parse_args(["--stdin", "--rule", "-1=x"].map(String::from)).unwrap();
+        let mut out = Vec::new();
+        let input = "-9223372036854775808\n".as_bytes();
+        let result = process_lines(&options.fizzy, input, &mut out, io::sink(), false);
+        assert!(result.is_ok());
+        assert_eq!(out, b"x\n");
+    }
+
+    #[test]
+    fn args_with_rules() {
+        let args = ["--stdin", "--rule", "2=even", "--lenient"].map(String::from);
+        let options = parse_args(args).unwrap();
+        assert!(options.stdin && options.lenient);
+        assert_eq!(options.fizzy.eval(4), "even");
+        assert_eq!(options.fizzy.eval(3), "3");
+    }
+
+    #[test]
+    fn args_rejected() {
+        assert!(parse_args(["--rule".to_string()]).is_err());
+        assert!(parse_args(["--rule", "0=x"].map(String::from)).is_err());
+        assert!(parse_args(["--lenient".to_string()]).is_err());
+        assert!(parse_args(["--stdin", "10"].map(String::from)).is_err());
+    }
 }
@@ -0,0 +1,202 @@
+fn soundex_digit(c: char) -> Option<u8> {
+    match c {
+        'B' | 'F' | 'P' | 'V' => Some(b'1'),
+        'C' | 'G' | 'J' | 'K' | 'Q' | 'S' | 'X' | 'Z' => Some(b'2'),
+        'D' | 'T' => Some(b'3'),
+        'L' => Some(b'4'),
+        'M' | 'N' => Some(b'5'),
+        'R' => Some(b'6'),
+        _ => None,
+    }
+}
+
+/// American Soundex: the first letter followed by three digits. Non-ASCII
+/// letters and other characters are ignored; the result is empty if `s`
+/// contains no ASCII letters.
+pub fn soundex(s: &str) -> String {
+    let mut letters = s
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .map(|c| c.to_ascii_uppercase());
+    let Some(first) = letters.next() else {
+        return String::new();
+    };
+    let mut code = String::with_capacity(4);
+    code.push(first);
+    let mut last = soundex_digit(first);
+    for c in letters {
+        match c {
+            // H and W do not separate letters with the same digit.
+            'H' | 'W' => {}
+            'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => last = None,
+            _ => {
+                let digit = soundex_digit(c);
+                if digit != last {
+                    code.extend(digit.map(char::from));
+                }
+                last = digit;
+            }
+        }
+        if code.len() == 4 {
+            return code;
+        }
+    }
+    while code.len() < 4 {
+        code.push('0');
+    }
+    code
+}
+
+fn is_vowel(c: u8) -> bool {
+    matches!(c, b'A' | b'E' | b'I' | b'O' | b'U')
+}
+
+/// The original Metaphone code of `s`, ignoring everything but ASCII
+/// letters. `0` stands for "th".
+pub fn metaphone(s: &str) -> String {
+    let mut word: Vec<u8> = s
+        .bytes()
+        .filter(u8::is_ascii_alphabetic)
+        .map(|b| b.to_ascii_uppercase())
+        .collect();
+    // Doubled letters sound once, except "CC" as in "accent".
+    word.dedup_by(|b, a| a == b && *a != b'C');
+    match word.as_slice() {
+        [b'A', b'E', ..] | [b'G' | b'K' | b'P', b'N', ..] | [b'W', b'R', ..] => {
+            word.remove(0);
+        }
+        [b'X', ..] => word[0] = b'S',
+        [b'W', b'H', ..] => {
+            word.remove(1);
+        }
+        _ => {}
+    }
+
+    let at = |i: usize| word.get(i).copied().unwrap_or(0);
+    let mut code = String::new();
+    for (i, &c) in word.iter().enumerate() {
+        let prev = if i == 0 { 0 } else { word[i - 1] };
+        let next = at(i + 1);
+        let after = at(i + 2);
+        match c {
+            b'A' | b'E' | b'I' | b'O' | b'U' => {
+                if i == 0 {
+                    code.push(c as char);
+                }
+            }
+            b'B' => {
+                if !(prev == b'M' && next == 0) {
+                    code.push('B');
+                }
+            }
+            b'C' => match next {
+                b'I' if after == b'A' => code.push('X'),
+                b'H' if prev == b'S' => code.push('K'),
+                b'H' => code.push('X'),
+                b'I' | b'E' | b'Y' if prev == b'S' => {}
+                b'I' | b'E' | b'Y' => code.push('S'),
+                _ => code.push('K'),
+            },
+            b'D' => {
+                if next == b'G' && matches!(after, b'E' | b'I' | b'Y') {
+                    code.push('J');
+                } else {
+                    code.push('T');
+                }
+            }
+            b'G' => {
+                let soft = matches!(next, b'I' | b'E' | b'Y');
+                // "GH" before a consonant, final "GN" or "GNED", and the G
+                // of "DGE" (already coded as J) are silent.
+                let silent = (next == b'H' && !is_vowel(after))
+                    || (next == b'N' && (after == 0 || word[i + 2..] == *b"ED"))
+                    || (prev == b'D' && soft);
+                if !silent {
+                    code.push(if soft { 'J' } else { 'K' });
+                }
+            }
+            b'H' => {
+                let after_modifier = matches!(prev, b'C' | b'S' | b'P' | b'T' | b'G');
+                let silent = after_modifier || (is_vowel(prev) && !is_vowel(next));
+                if !silent {
+                    code.push('H');
+                }
+            }
+            b'K' => {
+                if prev != b'C' {
+                    code.push('K');
+                }
+            }
+            b'P' => code.push(if next == b'H' { 'F' } else { 'P' }),
+            b'Q' => code.push('K'),
+            b'S' => {
+                if next == b'H' || (next == b'I' && matches!(after, b'O' | b'A')) {
+                    code.push('X');
+                } else {
+                    code.push('S');
+                }
+            }
+            b'T' => {
+                if next == b'I' && matches!(after, b'O' | b'A') {
+                    code.push('X');
+                } else if next == b'H' {
+                    code.push('0');
+                } else if !(next == b'C' && after == b'H') {
+                    code.push('T');
+                }
+            }
+            b'V' => code.push('F'),
+            b'W' | b'Y' => {
+                if is_vowel(next) {
+                    code.push(c as char);
+                }
+            }
+            b'X' => code.push_str("KS"),
+            b'Z' => code.push('S'),
+            _ => code.push(c as char),
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn soundex_known() {
+        assert_eq!(soundex("fizz"), "F200");
+        assert_eq!(soundex("buzz"), "B200");
+        assert_eq!(soundex("fizzbuzz"), "F212");
+        assert_eq!(soundex("Robert"), "R163");
+        assert_eq!(soundex("Rupert"), "R163");
+        assert_eq!(soundex("Ashcraft"), "A261");
+        assert_eq!(soundex("Tymczak"), "T522");
+        assert_eq!(soundex("Pfister"), "P236");
+        assert_eq!(soundex("Lee"), "L000");
+        assert_eq!(soundex("15"), "");
+    }
+
+    #[test]
+    fn metaphone_known() {
+        assert_eq!(metaphone("fizz"), "FS");
+        assert_eq!(metaphone("buzz"), "BS");
+        assert_eq!(metaphone("fizzbuzz"), "FSBS");
+        assert_eq!(metaphone("Philip"), "FLP");
+        assert_eq!(metaphone("Xavier"), "SFR");
+        assert_eq!(metaphone("Wright"), "RT");
+        assert_eq!(metaphone("Thumb"), "0M");
+        assert_eq!(metaphone("Knight"), "NT");
+        assert_eq!(metaphone("Science"), "SNS");
+        assert_eq!(metaphone("Judge"), "JJ");
+        assert_eq!(metaphone("42"), "");
+    }
+
+    #[test]
+    fn metaphone_silent_g() {
+        assert_eq!(metaphone("Sign"), "SN");
+        assert_eq!(metaphone("Signed"), "SNT");
+        assert_eq!(metaphone("Ginger"), "JNJR");
+        assert_eq!(metaphone("Ghost"), "KST");
+    }
+}
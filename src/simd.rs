@@ -0,0 +1,226 @@
+//! Batch evaluation for rule sets made only of divisor rules.
+//!
+//! Values are processed in chunks of [`LANES`]: every divisor is tested
+//! against the whole chunk at once, producing one bit mask per rule, and the
+//! labels are then built from the masks. The fixed-size inner loops are
+//! straightforward for the compiler to unroll and vectorize.
+//!
+//! [`Fizzy::apply_slice`] and [`Fizzy::apply_write`] take this path when the
+//! feature is enabled and every matcher is a divisor rule without a limit;
+//! other rule sets, and values left over after the last full chunk, go
+//! through the scalar path.
+
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::ops::Rem;
+
+use crate::{Condition, Fizzy, Threading};
+
+pub const LANES: usize = 8;
+
+/// Tests one divisor against a whole chunk; bit `i` is set when lane `i` is a
+/// multiple.
+pub(crate) type Mask<T> = fn(&[T; LANES], &T) -> u8;
+
+pub(crate) fn divisible_mask<T>(chunk: &[T; LANES], divisor: &T) -> u8
+where
+    T: Copy + Rem<Output = T> + PartialEq,
+{
+    let zero = *divisor % *divisor;
+    let mut mask = 0;
+    for (lane, value) in chunk.iter().enumerate() {
+        mask |= ((*value % *divisor == zero) as u8) << lane;
+    }
+    mask
+}
+
+impl<T, M: Threading> Fizzy<T, M> {
+    pub(crate) fn batch_slice(&self, values: &[T]) -> Option<Vec<String>>
+    where
+        T: Clone + Display,
+    {
+        let rules = self.batch_rules()?;
+        let mut labels = Vec::with_capacity(values.len());
+        let mut chunks = values.chunks_exact(LANES);
+        for chunk in &mut chunks {
+            self.eval_chunk(chunk.try_into().unwrap(), &rules, &mut labels);
+        }
+        labels.extend(chunks.remainder().iter().map(|val| self.eval(val.clone())));
+        Some(labels)
+    }
+
+    /// Writes full chunks while `iter` reports at least [`LANES`] values
+    /// left, and returns it for the scalar path to finish. Streaming sources
+    /// report no lower bound, so their labels are still written as soon as
+    /// each value arrives.
+    pub(crate) fn batch_write<I, W>(&self, mut iter: I, writer: &mut W) -> io::Result<I>
+    where
+        I: Iterator<Item = T>,
+        W: Write,
+        T: Clone + Display,
+    {
+        let Some(rules) = self.batch_rules() else {
+            return Ok(iter);
+        };
+        let mut labels = Vec::with_capacity(LANES);
+        while iter.size_hint().0 >= LANES {
+            let chunk = iter.by_ref().take(LANES).collect::<Vec<_>>();
+            match <&[T; LANES]>::try_from(chunk.as_slice()) {
+                Ok(chunk) => self.eval_chunk(chunk, &rules, &mut labels),
+                Err(_) => labels.extend(chunk.into_iter().map(|val| self.eval(val))),
+            }
+            for label in labels.drain(..) {
+                writeln!(writer, "{label}")?;
+            }
+        }
+        Ok(iter)
+    }
+
+    fn batch_rules(&self) -> Option<Vec<(&T, Mask<T>)>> {
+        self.matchers
+            .iter()
+            .map(|matcher| match (&matcher.condition, &matcher.limit) {
+                (Condition::Divisor { divisor, mask, .. }, None) => Some((divisor, *mask)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn eval_chunk(&self, chunk: &[T; LANES], rules: &[(&T, Mask<T>)], labels: &mut Vec<String>)
+    where
+        T: Display,
+    {
+        let masks = rules
+            .iter()
+            .map(|(divisor, mask)| mask(chunk, divisor))
+            .collect::<Vec<_>>();
+        for (lane, value) in chunk.iter().enumerate() {
+            let mut label = String::new();
+            for (matcher, mask) in self.matchers.iter().zip(&masks) {
+                if mask & (1 << lane) != 0 {
+                    label.push_str(&matcher.substitution);
+                }
+            }
+            if label.is_empty() {
+                label = value.to_string();
+            }
+            labels.push(label);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fizz_buzz, Matcher};
+
+    struct Lcg(u64);
+
+    impl Lcg {
+        fn next(&mut self) -> u64 {
+            self.0 = self
+                .0
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            self.0 >> 16
+        }
+    }
+
+    fn random_rules<T>(rng: &mut Lcg) -> Fizzy<T>
+    where
+        T: Copy + Rem<Output = T> + PartialEq + From<u8>,
+    {
+        let words = ["fizz", "buzz", "bam", "", "boom"];
+        let mut fizzer = Fizzy::new();
+        for _ in 0..rng.next() % 6 {
+            let divisor = T::from((rng.next() % 20 + 1) as u8);
+            let word = words[(rng.next() % words.len() as u64) as usize];
+            fizzer = fizzer.add_matcher(Matcher::divisible_by(divisor, word));
+        }
+        fizzer
+    }
+
+    fn write_all<T: Clone + Display>(fizzer: &Fizzy<T>, values: &[T]) -> String {
+        let mut out = Vec::new();
+        fizzer
+            .apply_write(values.iter().cloned(), &mut out)
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn scalar_lines<T: Clone + Display>(fizzer: &Fizzy<T>, values: &[T]) -> String {
+        let labels = fizzer.collect_labels(values.iter().cloned());
+        labels.iter().map(|label| format!("{label}\n")).collect()
+    }
+
+    #[test]
+    fn differential_u32() {
+        let mut rng = Lcg(0x5eed);
+        for _ in 0..8 {
+            let fizzer = random_rules::<u32>(&mut rng);
+            let values = (0..500_003).map(|_| rng.next() as u32).collect::<Vec<_>>();
+            assert!(fizzer.batch_rules().is_some());
+            let scalar = fizzer.collect_labels(values.iter().copied());
+            assert_eq!(fizzer.apply_slice(&values), scalar);
+        }
+    }
+
+    #[test]
+    fn differential_u64() {
+        let mut rng = Lcg(0xb022);
+        for _ in 0..8 {
+            let fizzer = random_rules::<u64>(&mut rng);
+            let values = (0..500_005).map(|_| rng.next()).collect::<Vec<_>>();
+            let scalar = fizzer.collect_labels(values.iter().copied());
+            assert_eq!(fizzer.apply_slice(&values), scalar);
+        }
+    }
+
+    #[test]
+    fn differential_write() {
+        let mut rng = Lcg(0x3717e);
+        for _ in 0..4 {
+            let fizzer = random_rules::<u64>(&mut rng);
+            let values = (0..100_007).map(|_| rng.next()).collect::<Vec<_>>();
+            assert_eq!(write_all(&fizzer, &values), scalar_lines(&fizzer, &values));
+        }
+    }
+
+    #[test]
+    fn write_batches_only_values_known_to_exist() {
+        let fizzer = fizz_buzz::<u32>();
+        let mut out = Vec::new();
+        let rest = fizzer.batch_write(1..=20, &mut out).unwrap();
+        assert_eq!(out.iter().filter(|&&byte| byte == b'\n').count(), 16);
+        assert_eq!(rest.collect::<Vec<_>>(), [17, 18, 19, 20]);
+
+        let mut out = Vec::new();
+        let streaming = (1..=20).filter(|_| true);
+        let rest = fizzer.batch_write(streaming, &mut out).unwrap();
+        assert!(out.is_empty());
+        assert_eq!(rest.count(), 20);
+    }
+
+    #[test]
+    fn opaque_rules_use_scalar_path() {
+        let fizzer = fizz_buzz::<u32>().add_matcher(Matcher::new(|n: u32| n > 10, "big"));
+        let values = (1..=20).collect::<Vec<u32>>();
+        assert!(fizzer.batch_rules().is_none());
+        let actual = fizzer.apply_slice(&values);
+        assert_eq!(actual, fizzer.collect_labels(values.iter().copied()));
+        assert_eq!(actual[14], "fizzbuzzbig");
+    }
+
+    #[test]
+    fn limited_rules_use_scalar_path() {
+        let fizzer = Fizzy::new()
+            .add_matcher(Matcher::divisible_by(3u64, "fizz").limit(2))
+            .add_matcher(Matcher::divisible_by(5, "buzz"));
+        let values = (1..=20).collect::<Vec<u64>>();
+        assert!(fizzer.batch_rules().is_none());
+        let actual = fizzer.apply_slice(&values);
+        assert_eq!(actual, fizzer.collect_labels(values.iter().copied()));
+        assert_eq!(actual[8], "9");
+        assert_eq!(write_all(&fizzer, &values), scalar_lines(&fizzer, &values));
+    }
+}
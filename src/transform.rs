@@ -0,0 +1,172 @@
+/// Rotates ASCII letters by 13 places, preserving case; everything else
+/// passes through.
+pub fn rot13(s: &str) -> String {
+    caesar_shift(s, 13)
+}
+
+/// Rotates ASCII letters `shift` places through the alphabet, wrapping at
+/// either end and preserving case; everything else passes through.
+pub fn caesar_shift(s: &str, shift: i32) -> String {
+    let shift = shift.rem_euclid(26) as u8;
+    s.chars()
+        .map(|c| {
+            let base = match c {
+                'a'..='z' => b'a',
+                'A'..='Z' => b'A',
+                _ => return c,
+            };
+            ((c as u8 - base + shift) % 26 + base) as char
+        })
+        .collect()
+}
+
+/// Uppercases the first character of each whitespace-separated word and
+/// lowercases the rest, keeping the whitespace as it was.
+pub fn title_case(s: &str) -> String {
+    let mut titled = String::with_capacity(s.len());
+    let mut word_start = true;
+    for c in s.chars() {
+        if word_start {
+            titled.extend(c.to_uppercase());
+        } else {
+            titled.extend(c.to_lowercase());
+        }
+        word_start = c.is_whitespace();
+    }
+    titled
+}
+
+/// Lowercases `s`, drops everything but alphanumerics, and joins the
+/// remaining words (split on whitespace and `-`) with single `-`s.
+pub fn slugify(s: &str) -> String {
+    let mut slug = String::with_capacity(s.len());
+    let mut pending_dash = false;
+    for c in s.chars() {
+        if c.is_alphanumeric() {
+            if pending_dash && !slug.is_empty() {
+                slug.push('-');
+            }
+            pending_dash = false;
+            slug.extend(c.to_lowercase());
+        } else if c.is_whitespace() || c == '-' {
+            pending_dash = true;
+        }
+    }
+    slug
+}
+
+/// The first character of each whitespace-separated word, uppercased.
+pub fn abbreviate(s: &str) -> String {
+    s.split_whitespace()
+        .filter_map(|word| word.chars().next())
+        .flat_map(char::to_uppercase)
+        .collect()
+}
+
+/// Puts `fill` between every pair of adjacent characters of `s`.
+pub fn interleave_chars(s: &str, fill: char) -> String {
+    let mut interleaved = String::with_capacity(s.len() * (1 + fill.len_utf8()));
+    for (i, c) in s.chars().enumerate() {
+        if i > 0 {
+            interleaved.push(fill);
+        }
+        interleaved.push(c);
+    }
+    interleaved
+}
+
+/// Greedily wraps `s` into lines of at most `width` characters, breaking
+/// only between whitespace-separated words; a longer word gets a line to
+/// itself. `s` is returned unchanged if it already fits.
+pub fn word_wrap(s: &str, width: usize) -> String {
+    if s.chars().count() <= width {
+        return s.to_string();
+    }
+    let mut wrapped = String::with_capacity(s.len());
+    let mut line_len = 0;
+    for word in s.split_whitespace() {
+        let len = word.chars().count();
+        if line_len > 0 && line_len + 1 + len <= width {
+            wrapped.push(' ');
+            line_len += 1;
+        } else if line_len > 0 {
+            wrapped.push('\n');
+            line_len = 0;
+        }
+        wrapped.push_str(word);
+        line_len += len;
+    }
+    wrapped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rot13_known() {
+        assert_eq!(rot13("fizz"), "svmm");
+        assert_eq!(rot13("buzz"), "ohmm");
+        assert_eq!(rot13("Hello, World 42!"), "Uryyb, Jbeyq 42!");
+        assert_eq!(rot13("fizz→"), "svmm→");
+    }
+
+    #[test]
+    fn caesar() {
+        assert_eq!(caesar_shift("fizz", 1), "gjaa");
+        assert_eq!(caesar_shift("gjaa", -1), "fizz");
+        assert_eq!(caesar_shift("Zz", 1), "Aa");
+        assert_eq!(caesar_shift("fizz", 0), "fizz");
+        assert_eq!(caesar_shift("fizz", 26 * 4 + 1), "gjaa");
+        assert_eq!(caesar_shift("fizz", -27), caesar_shift("fizz", -1));
+        assert_eq!(caesar_shift("buzz", 13), rot13("buzz"));
+    }
+
+    #[test]
+    fn title() {
+        assert_eq!(title_case("fizz"), "Fizz");
+        assert_eq!(title_case("fizz buzz"), "Fizz Buzz");
+        assert_eq!(title_case("fIZZ  BuZz"), "Fizz  Buzz");
+        assert_eq!(title_case("élan vital"), "Élan Vital");
+        assert_eq!(title_case("ßa"), "SSa");
+        assert_eq!(title_case(""), "");
+    }
+
+    #[test]
+    fn slug() {
+        assert_eq!(slugify("fizzbuzz"), "fizzbuzz");
+        assert_eq!(slugify("Fizz Buzz"), "fizz-buzz");
+        assert_eq!(slugify("fizz!!"), "fizz");
+        assert_eq!(slugify("  Fizz --  Buzz's  Wham! "), "fizz-buzzs-wham");
+        assert_eq!(slugify("Über 15"), "über-15");
+        assert_eq!(slugify("!?"), "");
+    }
+
+    #[test]
+    fn initials() {
+        assert_eq!(abbreviate("fizz"), "F");
+        assert_eq!(abbreviate("fizz buzz"), "FB");
+        assert_eq!(abbreviate(" fizz  buzz wham "), "FBW");
+        assert_eq!(abbreviate("über alles"), "ÜA");
+        assert_eq!(abbreviate(""), "");
+    }
+
+    #[test]
+    fn interleave() {
+        assert_eq!(interleave_chars("fizz", '.'), "f.i.z.z");
+        assert_eq!(interleave_chars("buzz", ' '), "b u z z");
+        assert_eq!(interleave_chars("fizz", '🔥'), "f🔥i🔥z🔥z");
+        assert_eq!(interleave_chars("é", '.'), "é");
+        assert_eq!(interleave_chars("", '.'), "");
+    }
+
+    #[test]
+    fn wrap() {
+        assert_eq!(word_wrap("fizz buzz", 10), "fizz buzz");
+        assert_eq!(word_wrap("fizz buzz wham bang", 10), "fizz buzz\nwham bang");
+        assert_eq!(word_wrap("fizz buzz wham", 4), "fizz\nbuzz\nwham");
+        assert_eq!(word_wrap("fizzbuzzwham a", 5), "fizzbuzzwham\na");
+        assert_eq!(word_wrap("fizz   buzz", 5), "fizz\nbuzz");
+        assert_eq!(word_wrap("", 0), "");
+    }
+}
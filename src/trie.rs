@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+
+/// A set of strings keyed character by character; `S` is the stored string
+/// type.
+#[derive(Clone, Debug)]
+pub struct Trie<S> {
+    root: Node<S>,
+    len: usize,
+}
+
+#[derive(Clone, Debug)]
+struct Node<S> {
+    children: HashMap<char, Node<S>>,
+    item: Option<S>,
+}
+
+impl<S> Default for Node<S> {
+    fn default() -> Node<S> {
+        Node {
+            children: HashMap::new(),
+            item: None,
+        }
+    }
+}
+
+impl<S> Default for Trie<S> {
+    fn default() -> Trie<S> {
+        Trie::new()
+    }
+}
+
+impl<S> Trie<S> {
+    pub fn new() -> Trie<S> {
+        Trie {
+            root: Node::default(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn find(&self, key: &str) -> Option<&Node<S>> {
+        key.chars()
+            .try_fold(&self.root, |node, c| node.children.get(&c))
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.find(key).is_some_and(|node| node.item.is_some())
+    }
+}
+
+impl<S: AsRef<str>> Trie<S> {
+    /// Returns `false` if an equal string was already present.
+    pub fn insert(&mut self, item: S) -> bool {
+        let mut node = &mut self.root;
+        for c in item.as_ref().chars() {
+            node = node.children.entry(c).or_default();
+        }
+        if node.item.is_some() {
+            return false;
+        }
+        node.item = Some(item);
+        self.len += 1;
+        true
+    }
+
+    /// All stored strings beginning with `prefix`, sorted.
+    pub fn starts_with(&self, prefix: &str) -> Vec<&str> {
+        let mut found = Vec::new();
+        let mut pending: Vec<&Node<S>> = self.find(prefix).into_iter().collect();
+        while let Some(node) = pending.pop() {
+            if let Some(item) = &node.item {
+                found.push(item.as_ref());
+            }
+            pending.extend(node.children.values());
+        }
+        found.sort_unstable();
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insert_and_lookup() {
+        let mut trie = Trie::new();
+        assert!(trie.insert("fizz".to_string()));
+        assert!(trie.insert("fizzbuzz".to_string()));
+        assert!(trie.insert("buzz".to_string()));
+        assert!(!trie.insert("fizz".to_string()));
+        assert_eq!(trie.len(), 3);
+
+        assert!(trie.contains("fizz"));
+        assert!(!trie.contains("fiz"));
+        assert_eq!(trie.starts_with("fi"), ["fizz", "fizzbuzz"]);
+        assert_eq!(trie.starts_with(""), ["buzz", "fizz", "fizzbuzz"]);
+        assert!(trie.starts_with("x").is_empty());
+    }
+}